@@ -0,0 +1,7 @@
+// cell state of an automaton; the default state is the one all cells have before the initial state is set
+pub trait CellState: Copy + Eq + Default {
+    const COUNT: usize;
+
+    fn from_index(index: usize) -> Self;
+    fn get_index(&self) -> usize;
+}
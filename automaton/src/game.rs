@@ -0,0 +1,248 @@
+use std::mem;
+
+use rayon::prelude::*;
+
+use crate::cell_state::CellState;
+use crate::rules::{NeighbourCounts, Rules};
+use crate::topology::Topology;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    // the last iteration has not changed anything
+    Stabilised,
+    ReachedIterationCap,
+}
+
+pub struct SimulationReport<S: CellState> {
+    pub outcome: Outcome,
+    // one entry per iteration
+    pub changes_counts: Vec<usize>,
+    // number of cells in every state (indexed by CellState::get_index()) initially and after every iteration
+    pub population_history: Vec<Vec<usize>>,
+    pub final_states: Vec<S>,
+}
+
+impl<S: CellState> SimulationReport<S> {
+    pub fn get_iterations_count(&self) -> usize {
+        self.changes_counts.len()
+    }
+}
+
+// number of consecutive pending cells evaluated by one task in parallel scan
+const PARALLEL_CHUNK_SIZE: usize = 1024;
+
+// frontiers containing more than 1/DENSE_FRONTIER_RATIO of all cells are not listed, but found by scanning all cells
+const DENSE_FRONTIER_RATIO: usize = 16;
+
+pub struct Game<S: CellState, T: Topology, R: Rules<S>> {
+    rules: R,
+    topology: T,
+    states: Vec<S>,
+    // S::COUNT counters per cell
+    neighbour_counts: Vec<u16>,
+    // state of the neighbours outside of the topology
+    border_state: S,
+    // number of cells in every state
+    population: Vec<usize>,
+    // cells which have changed, or whose neighbours have changed, since they were last evaluated;
+    // other cells cannot change on the next step
+    is_pending: Vec<bool>,
+    // pending cells, unless there are more than max_pending_cells_count of them
+    pending_cells: Vec<usize>,
+    max_pending_cells_count: usize,
+}
+
+fn mark_pending(pending_cells: &mut Vec<usize>, max_pending_cells_count: usize, is_pending: &mut [bool], cell: usize) {
+    if !is_pending[cell] {
+        is_pending[cell] = true;
+        if pending_cells.len() <= max_pending_cells_count {
+            pending_cells.push(cell);
+        }
+    }
+}
+
+impl<S: CellState, T: Topology, R: Rules<S>> Game<S, T, R> {
+    // all cells, and the border, are in the default state
+    pub fn new(rules: R, topology: T) -> Self {
+        let cells_count = topology.get_cells_count();
+        let mut neighbour_counts = vec![0; cells_count * S::COUNT];
+        let default_index = S::default().get_index();
+        for cell in 0..cells_count {
+            neighbour_counts[cell * S::COUNT + default_index] = (topology.get_neighbours_count(cell) + topology.get_border_neighbours_count(cell)) as u16;
+        }
+
+        Game {
+            rules,
+            topology,
+            states: vec![S::default(); cells_count],
+            neighbour_counts,
+            border_state: S::default(),
+            population: (0..S::COUNT).map(|index| if index == default_index { cells_count } else { 0 }).collect(),
+            is_pending: vec![true; cells_count],
+            // list overflows right away, so that the first step scans all cells
+            pending_cells: (0..cells_count / DENSE_FRONTIER_RATIO + 1).collect(),
+            max_pending_cells_count: cells_count / DENSE_FRONTIER_RATIO,
+        }
+    }
+
+    pub fn set_border_state(&mut self, border_state: S) {
+        let old_index = self.border_state.get_index();
+        let new_index = border_state.get_index();
+        self.border_state = border_state;
+        if old_index == new_index {
+            return;
+        }
+
+        for cell in 0..self.states.len() {
+            let border_neighbours_count = self.topology.get_border_neighbours_count(cell) as u16;
+            if border_neighbours_count > 0 {
+                self.neighbour_counts[cell * S::COUNT + old_index] -= border_neighbours_count;
+                self.neighbour_counts[cell * S::COUNT + new_index] += border_neighbours_count;
+                mark_pending(&mut self.pending_cells, self.max_pending_cells_count, &mut self.is_pending, cell);
+            }
+        }
+    }
+
+    pub fn get_border_state(&self) -> S {
+        self.border_state
+    }
+
+    pub fn from_states(rules: R, topology: T, states: &[S]) -> Self {
+        let mut game = Self::new(rules, topology);
+        for (cell, &state) in states.iter().enumerate() {
+            game.update_cell(cell, state);
+        }
+
+        game
+    }
+
+    // updates the state of this cell for it and for the cells counting it as a neighbour
+    pub fn update_cell(&mut self, cell: usize, new_state: S) {
+        let old_state = self.states[cell];
+        if old_state == new_state {
+            return;
+        }
+
+        self.states[cell] = new_state;
+        self.population[old_state.get_index()] -= 1;
+        self.population[new_state.get_index()] += 1;
+        let neighbour_counts = &mut self.neighbour_counts;
+        let pending_cells = &mut self.pending_cells;
+        let max_pending_cells_count = self.max_pending_cells_count;
+        let is_pending = &mut self.is_pending;
+        mark_pending(pending_cells, max_pending_cells_count, is_pending, cell);
+        self.topology.for_each_dependent(cell, |dependent| {
+            neighbour_counts[dependent * S::COUNT + old_state.get_index()] -= 1;
+            neighbour_counts[dependent * S::COUNT + new_state.get_index()] += 1;
+            mark_pending(pending_cells, max_pending_cells_count, is_pending, dependent);
+        });
+    }
+
+    // cells to evaluate on this step, in ascending order;
+    // unless most cells are pending, costs are proportional to their number, not to the number of all cells
+    fn take_pending_cells(&mut self) -> Vec<usize> {
+        let mut cells = mem::take(&mut self.pending_cells);
+        if cells.len() > self.max_pending_cells_count {
+            // the list is incomplete, and scanning the flags in order is cheaper than sorting anyway
+            cells.clear();
+            cells.extend(self.is_pending.iter().enumerate().filter_map(|(cell, &is_pending)| if is_pending { Some(cell) } else { None }));
+        } else {
+            cells.sort_unstable();
+        }
+
+        for &cell in &cells {
+            self.is_pending[cell] = false;
+        }
+        cells
+    }
+
+    fn get_next_state(&self, cell: usize) -> S {
+        let neighbour_counts = NeighbourCounts::new(&self.neighbour_counts[cell * S::COUNT..(cell + 1) * S::COUNT]);
+        self.rules.get_next_state(self.states[cell], neighbour_counts)
+    }
+
+    fn get_change(&self, cell: usize) -> Option<(usize, S)> {
+        let next_state = self.get_next_state(cell);
+        if next_state != self.states[cell] {
+            Some((cell, next_state))
+        } else {
+            None
+        }
+    }
+
+    fn apply_changes(&mut self, changes: Vec<(usize, S)>) -> usize {
+        let changes_count = changes.len();
+        for (cell, new_state) in changes {
+            self.update_cell(cell, new_state);
+        }
+
+        changes_count
+    }
+
+    pub fn next_step(&mut self) -> usize {
+        let cells = self.take_pending_cells();
+        let changes: Vec<_> = cells.iter().filter_map(|&cell| self.get_change(cell)).collect();
+        self.apply_changes(changes)
+    }
+
+    pub fn get_topology(&self) -> &T {
+        &self.topology
+    }
+
+    pub fn get_rules(&self) -> &R {
+        &self.rules
+    }
+
+    pub fn get_state(&self, cell: usize) -> S {
+        self.states[cell]
+    }
+
+    pub fn get_states(&self) -> &[S] {
+        &self.states
+    }
+
+    pub fn get_count_of_cells_for_state(&self, state: S) -> usize {
+        self.population[state.get_index()]
+    }
+
+    // number of cells in every state, indexed by CellState::get_index()
+    pub fn get_population(&self) -> &[usize] {
+        &self.population
+    }
+
+    // steps until nothing changes, but at most max_iterations times
+    pub fn run_until_stable(&mut self, max_iterations: usize) -> SimulationReport<S> {
+        let mut changes_counts = vec![];
+        let mut population_history = vec![self.population.clone()];
+        let mut outcome = Outcome::ReachedIterationCap;
+        while changes_counts.len() < max_iterations {
+            let changes_count = self.next_step();
+            changes_counts.push(changes_count);
+            population_history.push(self.population.clone());
+            if changes_count == 0 {
+                outcome = Outcome::Stabilised;
+                break;
+            }
+        }
+
+        SimulationReport {
+            outcome,
+            changes_counts,
+            population_history,
+            final_states: self.states.clone(),
+        }
+    }
+}
+
+impl<S: CellState + Send + Sync, T: Topology + Sync, R: Rules<S> + Sync> Game<S, T, R> {
+    // same result as next_step, but cells are evaluated in parallel;
+    // changes are still collected in cell order and applied sequentially
+    pub fn next_step_parallel(&mut self) -> usize {
+        let cells = self.take_pending_cells();
+        let changes: Vec<_> = cells.par_iter()
+            .with_min_len(PARALLEL_CHUNK_SIZE)
+            .filter_map(|&cell| self.get_change(cell))
+            .collect();
+        self.apply_changes(changes)
+    }
+}
@@ -0,0 +1,326 @@
+use crate::topology::Topology;
+
+pub type Location<const D: usize> = [usize; D];
+pub type Offset<const D: usize> = [isize; D];
+
+// all 3^D-1 offsets to the cells touching this one, in lexicographic order
+pub fn build_moore_offsets<const D: usize>() -> Vec<Offset<D>> {
+    let mut result = vec![];
+    let mut offset = [-1; D];
+    loop {
+        if offset.iter().any(|&diff| diff != 0) {
+            result.push(offset);
+        }
+
+        let mut dimension = D;
+        loop {
+            if dimension == 0 {
+                return result;
+            }
+            dimension -= 1;
+            if offset[dimension] < 1 {
+                offset[dimension] += 1;
+                break;
+            }
+            offset[dimension] = -1;
+        }
+    }
+}
+
+// 2*D offsets to the cells sharing a face with this one
+pub fn build_von_neumann_offsets<const D: usize>() -> Vec<Offset<D>> {
+    let mut result = vec![];
+    for dimension in 0..D {
+        for &diff in &[-1, 1] {
+            let mut offset = [0; D];
+            offset[dimension] = diff;
+            result.push(offset);
+        }
+    }
+    result
+}
+
+// six neighbours on a hexagonal grid in axial coordinates, where (1, 1) is a neighbour but (1, -1) is not
+pub const HEX_OFFSETS: [Offset<2>; 6] = [
+    [-1,  0],
+    [ 0,  1],
+    [ 1,  1],
+    [ 1,  0],
+    [ 0, -1],
+    [-1, -1],
+];
+
+// what happens to neighbours which would be outside of the grid
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Boundary {
+    // they do not exist
+    #[default]
+    Wall,
+    // they are taken from the opposite side (torus)
+    Wrap,
+    // the grid is reflected about its edge cells, i.e. coordinate -1 is the same as coordinate 1,
+    // so that no cell is its own neighbour (in dimensions of size 1, there are no neighbours at all)
+    Mirror,
+    // they are border cells in a fixed state (see Game::set_border_state)
+    Fixed,
+}
+
+// D-dimensional box of cells
+pub struct Grid<const D: usize> {
+    shape: [usize; D],
+    offsets: Vec<Offset<D>>,
+    boundary: Boundary,
+    // for cells far enough from the edges, all dependent cells are at fixed index differences
+    dependent_index_offsets: Vec<isize>,
+    reach: [usize; D],
+}
+
+impl<const D: usize> Grid<D> {
+    pub fn new(shape: [usize; D], offsets: Vec<Offset<D>>) -> Self {
+        let mut reach = [0; D];
+        for offset in &offsets {
+            for dimension in 0..D {
+                reach[dimension] = reach[dimension].max(offset[dimension].unsigned_abs());
+            }
+        }
+
+        let mut grid = Grid {
+            shape,
+            offsets,
+            boundary: Boundary::Wall,
+            dependent_index_offsets: vec![],
+            reach,
+        };
+
+        grid.dependent_index_offsets = grid.offsets.iter().map(|offset| -grid.get_index_offset(*offset)).collect();
+        grid
+    }
+
+    pub fn new_moore(shape: [usize; D]) -> Self {
+        Self::new(shape, build_moore_offsets())
+    }
+
+    pub fn with_boundary(mut self, boundary: Boundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    pub fn get_boundary(&self) -> Boundary {
+        self.boundary
+    }
+
+    fn get_index_offset(&self, offset: Offset<D>) -> isize {
+        offset.iter().zip(self.shape.iter()).fold(0, |result, (&diff, &size)| result * size as isize + diff)
+    }
+
+    pub fn get_shape(&self) -> [usize; D] {
+        self.shape
+    }
+
+    pub fn get_offsets(&self) -> &[Offset<D>] {
+        &self.offsets
+    }
+
+    pub fn get_index(&self, location: Location<D>) -> usize {
+        location.iter().zip(self.shape.iter()).fold(0, |result, (&coordinate, &size)| result * size + coordinate)
+    }
+
+    pub fn get_location(&self, index: usize) -> Location<D> {
+        let mut location = [0; D];
+        let mut rest = index;
+        for dimension in (0..D).rev() {
+            location[dimension] = rest % self.shape[dimension];
+            rest /= self.shape[dimension];
+        }
+        location
+    }
+
+    // coordinate inside the grid which the given (possibly outside) coordinate refers to
+    fn resolve_coordinate(&self, coordinate: isize, dimension: usize) -> Option<usize> {
+        let size = self.shape[dimension] as isize;
+        if coordinate >= 0 && coordinate < size {
+            return Some(coordinate as usize);
+        }
+
+        match self.boundary {
+            Boundary::Wall | Boundary::Fixed => None,
+            Boundary::Wrap => Some(coordinate.rem_euclid(size) as usize),
+            Boundary::Mirror if size == 1 => None,
+            Boundary::Mirror => {
+                let period = 2 * (size - 1);
+                let reflected = coordinate.rem_euclid(period);
+                Some(if reflected < size { reflected } else { period - reflected } as usize)
+            },
+        }
+    }
+
+    pub fn get_neighbour_location(&self, location: Location<D>, offset: Offset<D>) -> Option<Location<D>> {
+        let mut result = location;
+        for dimension in 0..D {
+            result[dimension] = self.resolve_coordinate(location[dimension] as isize + offset[dimension], dimension)?;
+        }
+        Some(result)
+    }
+
+    // all coordinates within reach of the grid which refer to the given coordinate
+    fn get_coordinate_aliases(&self, coordinate: usize, dimension: usize) -> Vec<isize> {
+        let reach = self.reach[dimension] as isize;
+        (-reach..self.shape[dimension] as isize + reach)
+            .filter(|&alias| self.resolve_coordinate(alias, dimension) == Some(coordinate))
+            .collect()
+    }
+
+    // cells whose neighbours are all inside the grid, and which are not referred to from outside of it
+    // (mirrored coordinates up to reach outside of the grid refer to the cells up to reach inside of it)
+    fn is_interior(&self, location: Location<D>) -> bool {
+        let margin_factor = if self.boundary == Boundary::Mirror { 2 } else { 1 };
+        (0..D).all(|dimension| {
+            let margin = margin_factor * self.reach[dimension];
+            location[dimension] >= margin && location[dimension] + margin < self.shape[dimension]
+        })
+    }
+}
+
+impl<const D: usize> Topology for Grid<D> {
+    fn get_cells_count(&self) -> usize {
+        self.shape.iter().product()
+    }
+
+    fn get_neighbours_count(&self, cell: usize) -> usize {
+        let location = self.get_location(cell);
+        if self.is_interior(location) {
+            return self.offsets.len();
+        }
+
+        self.offsets.iter().filter(|&&offset| self.get_neighbour_location(location, offset).is_some()).count()
+    }
+
+    fn get_border_neighbours_count(&self, cell: usize) -> usize {
+        match self.boundary {
+            Boundary::Fixed => self.offsets.len() - self.get_neighbours_count(cell),
+            _ => 0,
+        }
+    }
+
+    // cell counts its neighbours at the given offsets, so it is counted by the cells at the opposite offsets;
+    // near the edges, wrapped or mirrored coordinates are taken into account as well
+    fn for_each_dependent<F: FnMut(usize)>(&self, cell: usize, mut f: F) {
+        let location = self.get_location(cell);
+        if self.is_interior(location) {
+            for &index_offset in &self.dependent_index_offsets {
+                f((cell as isize + index_offset) as usize);
+            }
+            return;
+        }
+
+        let aliases: Vec<_> = (0..D).map(|dimension| self.get_coordinate_aliases(location[dimension], dimension)).collect();
+        for &offset in &self.offsets {
+            // every combination of aliases, minus offset, which is inside the grid
+            let candidates: Vec<Vec<usize>> = (0..D)
+                .map(|dimension| {
+                    aliases[dimension].iter()
+                        .map(|&alias| alias - offset[dimension])
+                        .filter(|&coordinate| coordinate >= 0 && coordinate < self.shape[dimension] as isize)
+                        .map(|coordinate| coordinate as usize)
+                        .collect()
+                })
+                .collect();
+
+            if candidates.iter().any(|dimension_candidates| dimension_candidates.is_empty()) {
+                continue;
+            }
+
+            let mut choice = [0; D];
+            loop {
+                let mut dependent_location = [0; D];
+                for dimension in 0..D {
+                    dependent_location[dimension] = candidates[dimension][choice[dimension]];
+                }
+                f(self.get_index(dependent_location));
+
+                let mut dimension = D;
+                loop {
+                    if dimension == 0 {
+                        break;
+                    }
+                    dimension -= 1;
+                    choice[dimension] += 1;
+                    if choice[dimension] < candidates[dimension].len() {
+                        break;
+                    }
+                    choice[dimension] = 0;
+                }
+                if choice.iter().all(|&index| index == 0) {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_dependents<const D: usize>(grid: &Grid<D>, cell: usize) -> Vec<usize> {
+        let mut dependents = vec![];
+        grid.for_each_dependent(cell, |dependent| dependents.push(dependent));
+        dependents.sort_unstable();
+        dependents
+    }
+
+    // cells counting the given one as a neighbour, once per offset at which they do
+    fn get_dependents_brute_force<const D: usize>(grid: &Grid<D>, cell: usize) -> Vec<usize> {
+        let mut dependents = vec![];
+        for other in 0..grid.get_cells_count() {
+            for &offset in grid.get_offsets() {
+                if grid.get_neighbour_location(grid.get_location(other), offset) == Some(grid.get_location(cell)) {
+                    dependents.push(other);
+                }
+            }
+        }
+        dependents
+    }
+
+    #[test]
+    fn mirror_reflects_about_edge_cells() {
+        let grid = Grid::new_moore([3, 4]).with_boundary(Boundary::Mirror);
+        assert_eq!(grid.get_neighbour_location([0, 0], [-1, -1]), Some([1, 1]));
+        assert_eq!(grid.get_neighbour_location([0, 2], [-1, 0]), Some([1, 2]));
+        assert_eq!(grid.get_neighbour_location([2, 3], [1, 1]), Some([1, 2]));
+        assert_eq!(grid.get_neighbour_location([1, 3], [0, 1]), Some([1, 2]));
+    }
+
+    #[test]
+    fn mirror_cells_are_not_their_own_neighbours() {
+        let grid = Grid::new_moore([3, 4]).with_boundary(Boundary::Mirror);
+        for cell in 0..grid.get_cells_count() {
+            let location = grid.get_location(cell);
+            for &offset in grid.get_offsets() {
+                assert_ne!(grid.get_neighbour_location(location, offset), Some(location));
+            }
+        }
+    }
+
+    #[test]
+    fn mirror_has_no_neighbours_across_dimensions_of_size_1() {
+        let grid = Grid::new_moore([1, 3]).with_boundary(Boundary::Mirror);
+        assert_eq!(grid.get_neighbour_location([0, 1], [-1, 0]), None);
+        assert_eq!(grid.get_neighbour_location([0, 1], [0, 1]), Some([0, 2]));
+        assert_eq!(grid.get_neighbours_count(1), 2);
+    }
+
+    #[test]
+    fn dependents_match_neighbours() {
+        for &boundary in &[Boundary::Wall, Boundary::Wrap, Boundary::Mirror, Boundary::Fixed] {
+            for &shape in &[[1, 1], [1, 5], [2, 2], [2, 5], [3, 3], [5, 6]] {
+                for offsets in [build_moore_offsets(), build_von_neumann_offsets(), HEX_OFFSETS.to_vec()] {
+                    let grid = Grid::new(shape, offsets).with_boundary(boundary);
+                    for cell in 0..grid.get_cells_count() {
+                        assert_eq!(get_dependents(&grid, cell), get_dependents_brute_force(&grid, cell), "{:?} {:?} cell {}", boundary, shape, cell);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,20 @@
+// generic cellular automaton: every cell has a state, and its next state is determined by rules
+// from its current state and the number of neighbours in every state;
+// neighbourhood is determined by topology (bounded grid of any dimension, or an arbitrary graph);
+// SparseGame is the same on an unbounded grid
+
+mod cell_state;
+mod game;
+mod grid;
+mod life;
+mod rules;
+mod sparse;
+mod topology;
+
+pub use cell_state::CellState;
+pub use game::{Game, Outcome, SimulationReport};
+pub use grid::{build_moore_offsets, build_von_neumann_offsets, Boundary, Grid, Location, Offset, HEX_OFFSETS};
+pub use life::{LifeRules, LifeState};
+pub use rules::{MemoisedRules, NeighbourCounts, Rules};
+pub use sparse::{Point, SparseGame};
+pub use topology::{NeighbourLists, Topology};
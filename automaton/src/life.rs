@@ -0,0 +1,92 @@
+use std::error::Error;
+use std::str::FromStr;
+
+use crate::cell_state::CellState;
+use crate::rules::{NeighbourCounts, Rules};
+
+#[derive(Clone, Copy, Default, Eq, PartialEq)]
+pub enum LifeState {
+    #[default]
+    Dead = 0,
+    Alive = 1,
+}
+
+impl CellState for LifeState {
+    const COUNT: usize = 2;
+
+    fn from_index(index: usize) -> Self {
+        match index {
+            0 => Self::Dead,
+            1 => Self::Alive,
+            _ => panic!("unsupported number {}", index),
+        }
+    }
+
+    fn get_index(&self) -> usize {
+        *self as usize
+    }
+}
+
+// dead cells come alive at birth counts of alive neighbours, alive cells stay alive at survival counts
+pub struct LifeRules {
+    // indexed by the count of alive neighbours
+    is_birth: Vec<bool>,
+    is_survival: Vec<bool>,
+}
+
+fn build_count_table(counts: &[u16]) -> Vec<bool> {
+    let mut table = vec![false; counts.iter().max().map_or(0, |&max| max as usize + 1)];
+    for &count in counts {
+        table[count as usize] = true;
+    }
+    table
+}
+
+impl LifeRules {
+    pub fn new(birth: &[u16], survival: &[u16]) -> Self {
+        LifeRules {
+            is_birth: build_count_table(birth),
+            is_survival: build_count_table(survival),
+        }
+    }
+
+    pub fn is_birth(&self, alive_neighbours_count: u16) -> bool {
+        self.is_birth.get(alive_neighbours_count as usize).copied().unwrap_or(false)
+    }
+}
+
+// either single digits ("23"), or comma-separated numbers for counts above 9 ("2,3,12")
+fn parse_counts(s: &str) -> Result<Vec<u16>, Box<dyn Error>> {
+    if s.contains(',') {
+        return s.split(',').map(|count| Ok(count.trim().parse()?)).collect();
+    }
+
+    s.chars()
+        .map(|ch| ch.to_digit(10).map(|digit| digit as u16).ok_or_else(|| Box::from(format!("wrong count '{}'", ch))))
+        .collect()
+}
+
+// B/S notation, e.g. "B3/S23" or "B36/S23"
+impl FromStr for LifeRules {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let wrong_format = || Box::<dyn Error>::from(format!("expected rule string like 'B3/S23', got '{}'", s));
+        let (birth, survival) = s.split_once('/').ok_or_else(wrong_format)?;
+        let birth = birth.strip_prefix(|ch| ch == 'B' || ch == 'b').ok_or_else(wrong_format)?;
+        let survival = survival.strip_prefix(|ch| ch == 'S' || ch == 's').ok_or_else(wrong_format)?;
+
+        Ok(LifeRules::new(&parse_counts(birth)?, &parse_counts(survival)?))
+    }
+}
+
+impl Rules<LifeState> for LifeRules {
+    fn get_next_state(&self, current_state: LifeState, neighbour_counts: NeighbourCounts<LifeState>) -> LifeState {
+        let table = match current_state {
+            LifeState::Alive => &self.is_survival,
+            LifeState::Dead => &self.is_birth,
+        };
+
+        if table.get(neighbour_counts[LifeState::Alive] as usize).copied().unwrap_or(false) { LifeState::Alive } else { LifeState::Dead }
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
+use std::marker::PhantomData;
+use std::ops::Index;
+use std::sync::RwLock;
+
+use crate::cell_state::CellState;
+
+// number of neighbours in every state
+#[derive(Clone, Copy)]
+pub struct NeighbourCounts<'a, S: CellState> {
+    counts: &'a [u16],
+    phantom: PhantomData<S>,
+}
+
+impl<'a, S: CellState> NeighbourCounts<'a, S> {
+    pub fn new(counts: &'a [u16]) -> Self {
+        NeighbourCounts {
+            counts,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn get_counts(&self) -> &'a [u16] {
+        self.counts
+    }
+
+    pub fn get_total(&self) -> u16 {
+        self.counts.iter().sum()
+    }
+}
+
+impl<'a, S: CellState> Index<S> for NeighbourCounts<'a, S> {
+    type Output = u16;
+    fn index(&self, state: S) -> &u16 {
+        &self.counts[state.get_index()]
+    }
+}
+
+pub trait Rules<S: CellState> {
+    fn get_next_state(&self, current_state: S, neighbour_counts: NeighbourCounts<S>) -> S;
+}
+
+// so that several games can share the same rules
+impl<S: CellState, R: Rules<S>> Rules<S> for &R {
+    fn get_next_state(&self, current_state: S, neighbour_counts: NeighbourCounts<S>) -> S {
+        (*self).get_next_state(current_state, neighbour_counts)
+    }
+}
+
+// wrapped rules evaluated once per combination of state and neighbour counts, packed into u64 keys of sharded maps
+pub struct MemoisedRules<S: CellState, R: Rules<S>> {
+    rules: R,
+    max_neighbours_count: usize,
+    shards: Vec<RwLock<PackedKeyMap<S>>>,
+}
+
+const SHARDS_COUNT: usize = 16;
+
+// keys are already well-distributed small integers, so a multiplication is enough to hash them
+#[derive(Clone, Copy, Default)]
+struct PackedKeyHasher {
+    hash: u64,
+}
+
+impl Hasher for PackedKeyHasher {
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.write_u64(byte as u64);
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.hash = (self.hash ^ value).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    }
+}
+
+type PackedKeyMap<S> = HashMap<u64, S, BuildHasherDefault<PackedKeyHasher>>;
+
+impl<S: CellState, R: Rules<S>> MemoisedRules<S, R> {
+    // max_neighbours_count is the largest total of neighbour counts (including the border) of any cell
+    pub fn new(rules: R, max_neighbours_count: usize) -> Self {
+        MemoisedRules {
+            rules,
+            max_neighbours_count,
+            shards: (0..SHARDS_COUNT).map(|_| RwLock::new(PackedKeyMap::default())).collect(),
+        }
+    }
+
+    pub fn get_rules(&self) -> &R {
+        &self.rules
+    }
+
+    // state index and counts as digits in base max_neighbours_count + 1; None if they do not fit
+    fn get_key(&self, current_state: S, neighbour_counts: NeighbourCounts<S>) -> Option<u64> {
+        let radix = self.max_neighbours_count as u64 + 1;
+        neighbour_counts.get_counts().iter().try_fold(current_state.get_index() as u64, |key, &count| {
+            if (count as u64) < radix { key.checked_mul(radix)?.checked_add(count as u64) } else { None }
+        })
+    }
+}
+
+impl<S: CellState, R: Rules<S>> Rules<S> for MemoisedRules<S, R> {
+    fn get_next_state(&self, current_state: S, neighbour_counts: NeighbourCounts<S>) -> S {
+        let key = match self.get_key(current_state, neighbour_counts) {
+            Some(key) => key,
+            None => return self.rules.get_next_state(current_state, neighbour_counts),
+        };
+
+        let shard = &self.shards[key as usize % SHARDS_COUNT];
+        if let Some(&next_state) = shard.read().unwrap().get(&key) {
+            return next_state;
+        }
+
+        let next_state = self.rules.get_next_state(current_state, neighbour_counts);
+        shard.write().unwrap().insert(key, next_state);
+        next_state
+    }
+}
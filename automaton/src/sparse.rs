@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use crate::cell_state::CellState;
+use crate::grid::Offset;
+use crate::rules::{NeighbourCounts, Rules};
+
+// coordinates on an unbounded grid, may be negative
+pub type Point<const D: usize> = [isize; D];
+
+// unbounded D-dimensional grid which only stores cells not in the default state, so it grows on demand;
+// rules must keep a default-state cell without non-default neighbours in the default state
+pub struct SparseGame<S: CellState, R: Rules<S>, const D: usize> {
+    rules: R,
+    offsets: Vec<Offset<D>>,
+    // board is symmetric under negation of any of these coordinates, so only cells where they are non-negative are stored
+    mirrored_dimensions: Vec<usize>,
+    cells: HashMap<Point<D>, S>,
+}
+
+fn add_offset<const D: usize>(point: Point<D>, offset: Offset<D>, sign: isize) -> Point<D> {
+    let mut result = point;
+    for dimension in 0..D {
+        result[dimension] += sign * offset[dimension];
+    }
+    result
+}
+
+impl<S: CellState, R: Rules<S>, const D: usize> SparseGame<S, R, D> {
+    // all cells are in the default state
+    pub fn new(rules: R, offsets: Vec<Offset<D>>) -> Self {
+        SparseGame {
+            rules,
+            offsets,
+            mirrored_dimensions: vec![],
+            cells: HashMap::new(),
+        }
+    }
+
+    // only for offsets and boards symmetric in these dimensions; every stored cell stands for its mirror images
+    pub fn with_mirrored_dimensions(mut self, mirrored_dimensions: Vec<usize>) -> Self {
+        self.mirrored_dimensions = mirrored_dimensions;
+        self
+    }
+
+    fn is_canonical(&self, point: Point<D>) -> bool {
+        self.mirrored_dimensions.iter().all(|&dimension| point[dimension] >= 0)
+    }
+
+    fn get_canonical(&self, point: Point<D>) -> Point<D> {
+        let mut result = point;
+        for &dimension in &self.mirrored_dimensions {
+            result[dimension] = result[dimension].abs();
+        }
+        result
+    }
+
+    // all points which are the same as this canonical one under symmetry
+    fn get_mirror_images(&self, point: Point<D>) -> Vec<Point<D>> {
+        let mut result = vec![point];
+        for &dimension in &self.mirrored_dimensions {
+            if point[dimension] != 0 {
+                for image_index in 0..result.len() {
+                    let mut image = result[image_index];
+                    image[dimension] = -image[dimension];
+                    result.push(image);
+                }
+            }
+        }
+        result
+    }
+
+    // number of cells this canonical one stands for
+    fn get_weight(&self, point: Point<D>) -> usize {
+        1 << self.mirrored_dimensions.iter().filter(|&&dimension| point[dimension] != 0).count()
+    }
+
+    // in mirrored dimensions, the cell and its mirror images are set to the same state
+    pub fn set_state(&mut self, point: Point<D>, state: S) {
+        let point = self.get_canonical(point);
+        if state == S::default() {
+            self.cells.remove(&point);
+        } else {
+            self.cells.insert(point, state);
+        }
+    }
+
+    pub fn get_state(&self, point: Point<D>) -> S {
+        self.cells.get(&self.get_canonical(point)).copied().unwrap_or_default()
+    }
+
+    pub fn next_step(&mut self) -> usize {
+        // only cells which are not in the default state, or have such neighbours, can change;
+        // S::COUNT counters per such cell
+        let mut indices: HashMap<Point<D>, usize> = HashMap::with_capacity(self.cells.len() * self.offsets.len());
+        let mut neighbour_counts: Vec<u16> = vec![];
+        let mut get_counters_index = |point: Point<D>| {
+            *indices.entry(point).or_insert_with(|| {
+                neighbour_counts.resize(neighbour_counts.len() + S::COUNT, 0);
+                neighbour_counts.len() - S::COUNT
+            })
+        };
+
+        let mut increments = vec![];
+        for (&point, &state) in &self.cells {
+            get_counters_index(point);
+            // cell counts its neighbours at the given offsets, so it is counted by the cells at the opposite offsets;
+            // only the canonical cells are evaluated, but they may see this cell through any of its mirror images
+            for image in self.get_mirror_images(point) {
+                for &offset in &self.offsets {
+                    let dependent = add_offset(image, offset, -1);
+                    if self.is_canonical(dependent) {
+                        increments.push(get_counters_index(dependent) + state.get_index());
+                    }
+                }
+            }
+        }
+        for increment in increments {
+            neighbour_counts[increment] += 1;
+        }
+
+        let default_index = S::default().get_index();
+        let mut next_cells = HashMap::with_capacity(self.cells.len());
+        let mut changes_count = 0;
+        for (point, index) in indices {
+            let counts = &mut neighbour_counts[index..index + S::COUNT];
+            counts[default_index] = self.offsets.len() as u16 - counts.iter().sum::<u16>();
+
+            let current_state = self.get_state(point);
+            let next_state = self.rules.get_next_state(current_state, NeighbourCounts::new(counts));
+            if next_state != current_state {
+                changes_count += self.get_weight(point);
+            }
+            if next_state != S::default() {
+                next_cells.insert(point, next_state);
+            }
+        }
+
+        self.cells = next_cells;
+        changes_count
+    }
+
+    pub fn get_rules(&self) -> &R {
+        &self.rules
+    }
+
+    // only meaningful for non-default states, as there are infinitely many default cells
+    pub fn get_count_of_cells_for_state(&self, state: S) -> usize {
+        self.cells.iter()
+            .filter(|&(_, &cell_state)| cell_state == state)
+            .map(|(&point, _)| self.get_weight(point))
+            .sum()
+    }
+
+    // cells not in the default state, in no particular order (including mirror images)
+    pub fn get_cells(&self) -> impl Iterator<Item = (Point<D>, S)> + '_ {
+        self.cells.iter().flat_map(move |(&point, &state)| self.get_mirror_images(point).into_iter().map(move |image| (image, state)))
+    }
+
+    // smallest and largest coordinates of the cells not in the default state, if there are any
+    pub fn get_bounds(&self) -> Option<(Point<D>, Point<D>)> {
+        let mut points = self.cells.keys();
+        let first = *points.next()?;
+        let (mut min, max) = points.fold((first, first), |(mut min, mut max), point| {
+            for dimension in 0..D {
+                min[dimension] = min[dimension].min(point[dimension]);
+                max[dimension] = max[dimension].max(point[dimension]);
+            }
+            (min, max)
+        });
+        for &dimension in &self.mirrored_dimensions {
+            min[dimension] = -max[dimension];
+        }
+        Some((min, max))
+    }
+}
@@ -0,0 +1,84 @@
+// cells are identified by their indices in 0..get_cells_count()
+pub trait Topology {
+    fn get_cells_count(&self) -> usize;
+
+    // number of cells this cell counts as its neighbours
+    fn get_neighbours_count(&self, cell: usize) -> usize;
+
+    // number of neighbours outside of the topology, which are always in the border state
+    fn get_border_neighbours_count(&self, _cell: usize) -> usize {
+        0
+    }
+
+    // cells which count this cell as their neighbour;
+    // for symmetric neighbourhoods, these are just the neighbours of this cell
+    fn for_each_dependent<F: FnMut(usize)>(&self, cell: usize, f: F);
+}
+
+// so that several games can share the same topology
+impl<T: Topology> Topology for &T {
+    fn get_cells_count(&self) -> usize {
+        (*self).get_cells_count()
+    }
+
+    fn get_neighbours_count(&self, cell: usize) -> usize {
+        (*self).get_neighbours_count(cell)
+    }
+
+    fn get_border_neighbours_count(&self, cell: usize) -> usize {
+        (*self).get_border_neighbours_count(cell)
+    }
+
+    fn for_each_dependent<F: FnMut(usize)>(&self, cell: usize, f: F) {
+        (*self).for_each_dependent(cell, f)
+    }
+}
+
+// arbitrary neighbourhood graph, e.g. for neighbours determined by the initial state
+pub struct NeighbourLists {
+    neighbours_counts: Vec<usize>,
+    border_neighbours_counts: Vec<usize>,
+    dependents: Vec<Vec<usize>>,
+}
+
+impl NeighbourLists {
+    pub fn new(neighbours: Vec<Vec<usize>>) -> Self {
+        let mut dependents = vec![vec![]; neighbours.len()];
+        for (cell, cell_neighbours) in neighbours.iter().enumerate() {
+            for &neighbour in cell_neighbours {
+                dependents[neighbour].push(cell);
+            }
+        }
+
+        NeighbourLists {
+            neighbours_counts: neighbours.iter().map(|cell_neighbours| cell_neighbours.len()).collect(),
+            border_neighbours_counts: vec![0; neighbours.len()],
+            dependents,
+        }
+    }
+
+    pub fn with_border_neighbours_counts(mut self, border_neighbours_counts: Vec<usize>) -> Self {
+        self.border_neighbours_counts = border_neighbours_counts;
+        self
+    }
+}
+
+impl Topology for NeighbourLists {
+    fn get_cells_count(&self) -> usize {
+        self.dependents.len()
+    }
+
+    fn get_neighbours_count(&self, cell: usize) -> usize {
+        self.neighbours_counts[cell]
+    }
+
+    fn get_border_neighbours_count(&self, cell: usize) -> usize {
+        self.border_neighbours_counts[cell]
+    }
+
+    fn for_each_dependent<F: FnMut(usize)>(&self, cell: usize, mut f: F) {
+        for &dependent in &self.dependents[cell] {
+            f(dependent);
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use crate::instruction::Instruction;
+use crate::program::{Program, ProgramResult};
+
+pub struct Patch {
+    pub index: usize,
+    pub accumulator: i64,
+}
+
+// nodes are instruction indices, plus one extra exit node (index == instructions count)
+// which stands for any jump outside of the program
+pub struct ControlFlowGraph<'a> {
+    instructions: &'a [Instruction],
+    predecessors: Vec<Vec<usize>>,
+}
+
+impl<'a> ControlFlowGraph<'a> {
+    pub fn new(instructions: &'a [Instruction]) -> Self {
+        let mut predecessors = vec![vec![]; instructions.len() + 1];
+        for (index, &instruction) in instructions.iter().enumerate() {
+            let successor = Self::get_successor(instructions.len(), index, instruction);
+            predecessors[successor].push(index);
+        }
+
+        ControlFlowGraph {
+            instructions,
+            predecessors,
+        }
+    }
+
+    fn get_exit(&self) -> usize {
+        self.instructions.len()
+    }
+
+    fn get_successor(exit: usize, index: usize, instruction: Instruction) -> usize {
+        let target = match instruction {
+            Instruction::Jump(offset) => (index as i64).checked_add(offset),
+            Instruction::Accumulate(_) | Instruction::NoOp(_) => Some(index as i64 + 1),
+        };
+
+        match target {
+            Some(target) if target >= 0 && (target as usize) < exit => target as usize,
+            _ => exit,
+        }
+    }
+
+    fn get_flipped_instruction(instruction: Instruction) -> Option<Instruction> {
+        match instruction {
+            Instruction::Accumulate(_) => None,
+            Instruction::Jump(offset) => Some(Instruction::NoOp(offset)),
+            Instruction::NoOp(offset) => Some(Instruction::Jump(offset)),
+        }
+    }
+
+    // reverse reachability from the exit node
+    pub fn get_terminating_nodes(&self) -> Vec<bool> {
+        let mut terminating = vec![false; self.predecessors.len()];
+        let mut queue = vec![self.get_exit()];
+        terminating[self.get_exit()] = true;
+        while let Some(node) = queue.pop() {
+            for &predecessor in &self.predecessors[node] {
+                if !terminating[predecessor] {
+                    terminating[predecessor] = true;
+                    queue.push(predecessor);
+                }
+            }
+        }
+
+        terminating
+    }
+
+    // indices visited by the unpatched program, in execution order
+    fn get_original_path(&self) -> Vec<usize> {
+        let mut visited = vec![false; self.predecessors.len()];
+        let mut path = vec![];
+        let mut node = 0;
+        while node != self.get_exit() && !visited[node] {
+            visited[node] = true;
+            path.push(node);
+            node = Self::get_successor(self.get_exit(), node, self.instructions[node]);
+        }
+
+        path
+    }
+
+    // the flipped instruction has to be on the original path (otherwise nothing changes),
+    // and the flip has to lead into a node from which the original program terminates;
+    // such a node can not lead back to the original path, because the original path loops
+    pub fn find_patch(&self) -> Option<Patch> {
+        let terminating = self.get_terminating_nodes();
+        if self.instructions.is_empty() || terminating[0] {
+            return None;
+        }
+
+        for index in self.get_original_path() {
+            if let Some(flipped) = Self::get_flipped_instruction(self.instructions[index]) {
+                if terminating[Self::get_successor(self.get_exit(), index, flipped)] {
+                    return match Program::new(self.instructions, index as i64).run() {
+                        ProgramResult::Terminated(accumulator) => Some(Patch { index, accumulator }),
+                        ProgramResult::Looped(_) | ProgramResult::WaitingForInput(_) => panic!("patched program at #{} should have terminated", index),
+                    };
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [&str; 9] = ["nop +0", "acc +1", "jmp +4", "acc +3", "jmp -3", "acc -99", "acc +1", "jmp -4", "acc +6"];
+
+    fn parse_instructions(lines: &[&str]) -> Vec<Instruction> {
+        lines.iter().map(|line| line.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn example_is_patched_at_the_last_jump() {
+        let instructions = parse_instructions(&EXAMPLE);
+        let patch = ControlFlowGraph::new(&instructions).find_patch().unwrap();
+        assert_eq!(patch.index, 7);
+        assert_eq!(patch.accumulator, 8);
+    }
+
+    #[test]
+    fn terminating_program_needs_no_patch() {
+        let instructions = parse_instructions(&["nop +0", "acc +1", "jmp +1"]);
+        assert!(ControlFlowGraph::new(&instructions).find_patch().is_none());
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use regex::Regex;
+
+use crate::instruction_set::InstructionSet;
+use crate::parse_error::{split_tokens, ParseError, ParseErrorKind, ParseErrors, TokenError};
+use crate::register::Register;
+
+// mnemonics whose last argument is a relative offset, and thus can be a label
+const JUMP_MNEMONICS: [&str; 4] = ["jmp", "nop", "jz", "jnz"];
+
+struct SourceLine<'a> {
+    number: usize,
+    // labels with their 1-based columns
+    labels: Vec<(usize, &'a str)>,
+    // instruction with its 0-based position in the line
+    instruction: Option<(usize, &'a str)>,
+}
+
+impl<'a> SourceLine<'a> {
+    fn parse(number: usize, line: &'a str) -> Self {
+        lazy_static! {
+            static ref LABEL_RE: Regex = Regex::new(r"^\s*([A-Za-z_]\w*):(.*)$").unwrap();
+        }
+
+        let mut labels = vec![];
+        let mut offset = 0;
+        let mut rest = match line.find(';') {
+            Some(comment_start) => &line[..comment_start],
+            None => line,
+        };
+
+        while let Some(captures) = LABEL_RE.captures(rest) {
+            let label = captures.get(1).unwrap();
+            let remainder = captures.get(2).unwrap();
+            labels.push((offset + label.start() + 1, label.as_str()));
+            offset += remainder.start();
+            rest = remainder.as_str();
+        }
+
+        let trimmed = rest.trim();
+        SourceLine {
+            number,
+            labels,
+            instruction: if trimmed.is_empty() { None } else { Some((offset + rest.len() - rest.trim_start().len(), trimmed)) },
+        }
+    }
+}
+
+// replaces the label in the offset position (if any) with the relative offset; other columns are preserved
+fn resolve_labels(instruction: &str, index: usize, labels: &HashMap<&str, usize>) -> Result<String, TokenError> {
+    let tokens = split_tokens(instruction);
+    if tokens.len() > 1 && JUMP_MNEMONICS.contains(&tokens[0].1) {
+        let (column, token) = tokens[tokens.len() - 1];
+        if let Some(&target) = labels.get(token) {
+            return Ok(format!("{}{:+}", &instruction[..column - 1], target as i64 - index as i64));
+        }
+
+        let is_identifier = token.starts_with(|ch: char| ch.is_ascii_alphabetic() || ch == '_');
+        if is_identifier && token.parse::<Register>().is_err() {
+            return Err(TokenError::new(column, token, ParseErrorKind::UnknownLabel));
+        }
+    }
+
+    Ok(instruction.to_owned())
+}
+
+fn get_jump_errors<I: InstructionSet>(instructions: &[Option<I>]) -> Vec<(usize, ParseErrorKind)> {
+    let instructions_count = instructions.len();
+    instructions.iter().enumerate()
+        .filter_map(|(index, instruction)| {
+            let offset = instruction.as_ref()?.get_jump_offset()?;
+            // an overflowing target is out of range either way, so it is reported saturated
+            let target = (index as i64).checked_add(offset)
+                .unwrap_or(if offset < 0 { i64::MIN } else { i64::MAX });
+            // jumping right after the last instruction is the proper way to terminate
+            if target < 0 || target > instructions_count as i64 {
+                Some((index, ParseErrorKind::JumpOutOfRange { target, instructions_count }))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+// for programs that do not come from the source, e.g. decoded from bytecode; instruction numbers are used as lines
+pub fn validate_jumps<I: InstructionSet>(instructions: &[I]) -> Result<(), ParseErrors> {
+    let errors: Vec<_> = get_jump_errors(&instructions.iter().map(|&instruction| Some(instruction)).collect::<Vec<_>>())
+        .into_iter()
+        .map(|(index, kind)| ParseError { line: index + 1, column: 1, token: instructions[index].to_string(), kind })
+        .collect();
+
+    if errors.is_empty() { Ok(()) } else { Err(ParseErrors(errors)) }
+}
+
+// source consists of instructions in the usual text format, with optional "label:" prefixes
+// and ";" comments; labels can be used instead of offsets in jump instructions (and nop),
+// and take precedence over register names there.
+// all errors in the source are reported at once.
+pub fn assemble<I: FromStr<Err = TokenError> + InstructionSet>(source: &str) -> Result<Vec<I>, ParseErrors> {
+    let lines: Vec<_> = source.lines().enumerate().map(|(index, line)| SourceLine::parse(index + 1, line)).collect();
+    let mut errors = vec![];
+
+    let mut labels = HashMap::new();
+    let mut index = 0;
+    for line in &lines {
+        for &(column, label) in &line.labels {
+            if labels.insert(label, index).is_some() {
+                errors.push(TokenError::new(column, label, ParseErrorKind::DuplicateLabel).at_line(line.number, 0));
+            }
+        }
+        if line.instruction.is_some() {
+            index += 1;
+        }
+    }
+
+    let instruction_lines: Vec<_> = lines.iter()
+        .filter_map(|line| line.instruction.map(|(offset, instruction)| (line.number, offset, instruction)))
+        .collect();
+
+    let instructions: Vec<Option<I>> = instruction_lines.iter().enumerate()
+        .map(|(index, &(line_number, offset, instruction))| {
+            match resolve_labels(instruction, index, &labels).and_then(|resolved| resolved.parse()) {
+                Ok(instruction) => Some(instruction),
+                Err(e) => {
+                    errors.push(e.at_line(line_number, offset));
+                    None
+                },
+            }
+        })
+        .collect();
+
+    for (index, kind) in get_jump_errors(&instructions) {
+        let (line_number, offset, instruction) = instruction_lines[index];
+        errors.push(TokenError::new(1, instruction, kind).at_line(line_number, offset));
+    }
+
+    if !errors.is_empty() {
+        errors.sort_by_key(|error| (error.line, error.column));
+        return Err(ParseErrors(errors));
+    }
+
+    Ok(instructions.into_iter().map(|instruction| instruction.unwrap()).collect())
+}
+
+pub fn disassemble<I: Display>(instructions: &[I]) -> String {
+    instructions.iter().map(|instruction| format!("{}\n", instruction)).collect()
+}
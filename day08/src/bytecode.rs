@@ -0,0 +1,221 @@
+use std::error::Error;
+
+use crate::register::{Operand, Register};
+
+// "handheld game console", format version 1
+const MAGIC: &[u8; 4] = b"HGC1";
+
+const IMMEDIATE_TAG: u8 = 0xff;
+
+pub trait Bytecode: Sized {
+    fn encode(&self, writer: &mut BytecodeWriter);
+    fn decode(opcode: u8, reader: &mut BytecodeReader) -> Result<Self, Box<dyn Error>>;
+}
+
+pub struct BytecodeWriter {
+    bytes: Vec<u8>,
+}
+
+impl BytecodeWriter {
+    fn new() -> Self {
+        BytecodeWriter {
+            bytes: MAGIC.to_vec(),
+        }
+    }
+
+    pub fn write_opcode(&mut self, opcode: u8) {
+        self.bytes.push(opcode);
+    }
+
+    // zigzag-encoded LEB128, so that small offsets of both signs take a single byte
+    pub fn write_value(&mut self, value: i64) {
+        let mut zigzag = ((value << 1) ^ (value >> 63)) as u64;
+        loop {
+            let byte = (zigzag & 0x7f) as u8;
+            zigzag >>= 7;
+            if zigzag == 0 {
+                self.bytes.push(byte);
+                return;
+            }
+            self.bytes.push(byte | 0x80);
+        }
+    }
+
+    pub fn write_register(&mut self, register: Register) {
+        self.bytes.push(match register {
+            Register::Accumulator => 0,
+            Register::Named(number) => number + 1,
+        });
+    }
+
+    pub fn write_operand(&mut self, operand: Operand) {
+        match operand {
+            Operand::Register(register) => self.write_register(register),
+            Operand::Immediate(value) => {
+                self.bytes.push(IMMEDIATE_TAG);
+                self.write_value(value);
+            },
+        }
+    }
+}
+
+pub struct BytecodeReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BytecodeReader<'a> {
+    fn read_byte(&mut self) -> Result<u8, Box<dyn Error>> {
+        match self.bytes.get(self.position) {
+            Some(&byte) => {
+                self.position += 1;
+                Ok(byte)
+            },
+            None => Err(Box::from(format!("unexpected end of bytecode at offset {}", self.position))),
+        }
+    }
+
+    pub fn read_value(&mut self) -> Result<i64, Box<dyn Error>> {
+        let mut zigzag = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_byte()?;
+            if shift >= 64 {
+                return Err(Box::from(format!("value too long at offset {}", self.position)));
+            }
+            zigzag |= ((byte & 0x7f) as u64) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return Ok(((zigzag >> 1) as i64) ^ -((zigzag & 1) as i64));
+            }
+        }
+    }
+
+    pub fn read_register(&mut self) -> Result<Register, Box<dyn Error>> {
+        match self.read_byte()? {
+            0 => Ok(Register::Accumulator),
+            number @ 1..=26 => Ok(Register::Named(number - 1)),
+            other => Err(Box::from(format!("wrong register {} at offset {}", other, self.position - 1))),
+        }
+    }
+
+    pub fn read_operand(&mut self) -> Result<Operand, Box<dyn Error>> {
+        if self.bytes.get(self.position) == Some(&IMMEDIATE_TAG) {
+            self.position += 1;
+            Ok(Operand::Immediate(self.read_value()?))
+        } else {
+            Ok(Operand::Register(self.read_register()?))
+        }
+    }
+
+    pub fn get_unknown_opcode_error(&self, opcode: u8) -> Box<dyn Error> {
+        Box::from(format!("unknown opcode {} at offset {}", opcode, self.position - 1))
+    }
+}
+
+pub fn is_bytecode(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+pub fn encode_program<I: Bytecode>(instructions: &[I]) -> Vec<u8> {
+    let mut writer = BytecodeWriter::new();
+    writer.write_value(instructions.len() as i64);
+    for instruction in instructions {
+        instruction.encode(&mut writer);
+    }
+
+    writer.bytes
+}
+
+pub fn decode_program<I: Bytecode>(bytes: &[u8]) -> Result<Vec<I>, Box<dyn Error>> {
+    if !is_bytecode(bytes) {
+        return Err(Box::from("not a bytecode file"));
+    }
+
+    let mut reader = BytecodeReader {
+        bytes,
+        position: MAGIC.len(),
+    };
+    let count = reader.read_value()?;
+    let mut instructions = vec![];
+    for _ in 0..count {
+        let opcode = reader.read_byte()?;
+        instructions.push(I::decode(opcode, &mut reader)?);
+    }
+
+    if reader.position != reader.bytes.len() {
+        return Err(Box::from(format!("unexpected data after the last instruction at offset {}", reader.position)));
+    }
+
+    Ok(instructions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::Instruction;
+
+    const VALUES: [i64; 5] = [0, 1, -1, i64::MAX, i64::MIN];
+
+    fn read_values(bytes: &[u8]) -> Result<Vec<i64>, Box<dyn Error>> {
+        let mut reader = BytecodeReader {
+            bytes,
+            position: MAGIC.len(),
+        };
+        VALUES.iter().map(|_| reader.read_value()).collect()
+    }
+
+    fn get_example_bytecode() -> Vec<u8> {
+        let instructions = [Instruction::NoOp(0), Instruction::Accumulate(i64::MIN), Instruction::Jump(-1)];
+        encode_program(&instructions)
+    }
+
+    #[test]
+    fn values_round_trip() {
+        let mut writer = BytecodeWriter::new();
+        for &value in &VALUES {
+            writer.write_value(value);
+        }
+        assert_eq!(read_values(&writer.bytes).unwrap(), VALUES);
+    }
+
+    #[test]
+    fn small_values_take_one_byte() {
+        for &value in &[0, 1, -1, 63, -64] {
+            let mut writer = BytecodeWriter::new();
+            writer.write_value(value);
+            assert_eq!(writer.bytes.len(), MAGIC.len() + 1);
+        }
+    }
+
+    #[test]
+    fn truncated_value_is_rejected() {
+        let mut writer = BytecodeWriter::new();
+        for &value in &VALUES {
+            writer.write_value(value);
+        }
+        writer.bytes.pop();
+        assert!(read_values(&writer.bytes).is_err());
+    }
+
+    #[test]
+    fn program_round_trips() {
+        let instructions: Vec<Instruction> = decode_program(&get_example_bytecode()).unwrap();
+        let lines: Vec<_> = instructions.iter().map(|instruction| instruction.to_string()).collect();
+        assert_eq!(lines, ["nop +0", "acc -9223372036854775808", "jmp -1"]);
+    }
+
+    #[test]
+    fn truncated_program_is_rejected() {
+        let mut bytes = get_example_bytecode();
+        bytes.pop();
+        assert!(decode_program::<Instruction>(&bytes).is_err());
+    }
+
+    #[test]
+    fn trailing_data_is_rejected() {
+        let mut bytes = get_example_bytecode();
+        bytes.push(0);
+        assert!(decode_program::<Instruction>(&bytes).is_err());
+    }
+}
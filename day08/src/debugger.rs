@@ -0,0 +1,235 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::io::BufRead;
+use std::str::FromStr;
+
+use crate::instruction_set::InstructionSet;
+use crate::program::{ExecutionState, Program, StepResult};
+use crate::register::Register;
+
+enum Command {
+    Step(usize),
+    Continue,
+    Break(i64),
+    Delete(i64),
+    Input(i64),
+    Watch,
+    Unwatch,
+    Print,
+    Visited,
+    List,
+    Reset,
+    Help,
+    Quit,
+}
+
+impl FromStr for Command {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<_> = s.split_whitespace().collect();
+        match parts.as_slice() {
+            ["s"] | ["step"] => Ok(Self::Step(1)),
+            ["s", count] | ["step", count] => Ok(Self::Step(count.parse()?)),
+            ["c"] | ["continue"] => Ok(Self::Continue),
+            ["b", index] | ["break", index] => Ok(Self::Break(index.parse()?)),
+            ["d", index] | ["delete", index] => Ok(Self::Delete(index.parse()?)),
+            ["i", value] | ["input", value] => Ok(Self::Input(value.parse()?)),
+            ["w"] | ["watch"] => Ok(Self::Watch),
+            ["unwatch"] => Ok(Self::Unwatch),
+            ["p"] | ["print"] => Ok(Self::Print),
+            ["v"] | ["visited"] => Ok(Self::Visited),
+            ["l"] | ["list"] => Ok(Self::List),
+            ["r"] | ["reset"] => Ok(Self::Reset),
+            ["h"] | ["help"] => Ok(Self::Help),
+            ["q"] | ["quit"] => Ok(Self::Quit),
+            _ => Err(Box::from(format!("unknown command '{}'", s))),
+        }
+    }
+}
+
+enum StopReason {
+    Finished(StepResult),
+    Breakpoint,
+    WaitingForInput,
+    AccumulatorChanged(i64),
+}
+
+pub struct Debugger<'a, I: InstructionSet> {
+    program: Program<'a, I>,
+    state: ExecutionState,
+    breakpoints: HashSet<i64>,
+    watch_accumulator: bool,
+    finished: bool,
+}
+
+impl<'a, I: InstructionSet> Debugger<'a, I> {
+    pub fn new(program: Program<'a, I>) -> Self {
+        Debugger {
+            program,
+            state: ExecutionState::new(),
+            breakpoints: HashSet::new(),
+            watch_accumulator: false,
+            finished: false,
+        }
+    }
+
+    // single step; None means execution may proceed
+    fn step(&mut self) -> Option<StopReason> {
+        let old_accumulator = self.state.accumulator;
+        let old_output_length = self.state.output.len();
+        match self.program.step(&mut self.state) {
+            StepResult::Running => {},
+            StepResult::WaitingForInput(_) => return Some(StopReason::WaitingForInput),
+            result => {
+                self.finished = true;
+                return Some(StopReason::Finished(result));
+            },
+        }
+
+        for value in &self.state.output[old_output_length..] {
+            println!("Output: {}", value);
+        }
+
+        if self.watch_accumulator && self.state.accumulator != old_accumulator {
+            return Some(StopReason::AccumulatorChanged(old_accumulator));
+        }
+
+        if self.breakpoints.contains(&self.state.instruction_index) {
+            return Some(StopReason::Breakpoint);
+        }
+
+        None
+    }
+
+    fn report(&self, stop_reason: StopReason) {
+        match stop_reason {
+            StopReason::Finished(StepResult::Terminated(accumulator)) => println!("Program terminated: {}", accumulator),
+            StopReason::Finished(StepResult::Looped(accumulator)) => println!("Program looped at #{}: {}", self.state.instruction_index, accumulator),
+            StopReason::Finished(_) => {},
+            StopReason::WaitingForInput => println!("Program is waiting for input, use 'input <value>' to provide it"),
+            StopReason::Breakpoint => println!("Breakpoint hit at #{}", self.state.instruction_index),
+            StopReason::AccumulatorChanged(old_accumulator) => println!("Accumulator changed: {} -> {}", old_accumulator, self.state.accumulator),
+        }
+    }
+
+    fn print_current(&self) {
+        match self.program.get_instruction(self.state.instruction_index) {
+            Some(instruction) => println!("#{}: {}; accumulator: {}", self.state.instruction_index, instruction, self.state.accumulator),
+            None => println!("#{}: <end of program>; accumulator: {}", self.state.instruction_index, self.state.accumulator),
+        }
+
+        let registers: Vec<_> = self.state.registers.iter().enumerate()
+            .filter(|(_, &value)| value != 0)
+            .map(|(number, value)| format!("{}={}", Register::Named(number as u8), value))
+            .collect();
+        if !registers.is_empty() {
+            println!("Registers: {}", registers.join(", "));
+        }
+    }
+
+    fn execute(&mut self, command: Command) -> bool {
+        match command {
+            Command::Step(count) => {
+                for _ in 0..count {
+                    if self.finished {
+                        println!("Program is finished, use 'reset' to start over");
+                        break;
+                    }
+
+                    if let Some(stop_reason) = self.step() {
+                        self.report(stop_reason);
+                        break;
+                    }
+                }
+
+                self.print_current();
+            },
+            Command::Continue => {
+                if self.finished {
+                    println!("Program is finished, use 'reset' to start over");
+                    return true;
+                }
+
+                loop {
+                    if let Some(stop_reason) = self.step() {
+                        self.report(stop_reason);
+                        break;
+                    }
+                }
+
+                self.print_current();
+            },
+            Command::Break(index) => {
+                if index < 0 || (index as usize) >= self.program.get_instructions_count() {
+                    println!("Warning: #{} is outside of the program", index);
+                }
+                self.breakpoints.insert(index);
+                println!("Breakpoint set at #{}", index);
+            },
+            Command::Delete(index) => {
+                if self.breakpoints.remove(&index) {
+                    println!("Breakpoint at #{} removed", index);
+                } else {
+                    println!("No breakpoint at #{}", index);
+                }
+            },
+            Command::Input(value) => {
+                self.state.input.push_back(value);
+                println!("{} value(s) in input queue", self.state.input.len());
+            },
+            Command::Watch => {
+                self.watch_accumulator = true;
+                println!("Watching accumulator");
+            },
+            Command::Unwatch => {
+                self.watch_accumulator = false;
+                println!("Not watching accumulator");
+            },
+            Command::Print => self.print_current(),
+            Command::Visited => {
+                let mut visited: Vec<_> = self.state.visited_instructions.iter().cloned().collect();
+                visited.sort_unstable();
+                println!("Visited {} instructions: {}", visited.len(), visited.iter().map(|index| index.to_string()).collect::<Vec<_>>().join(", "));
+            },
+            Command::List => {
+                for index in 0..self.program.get_instructions_count() as i64 {
+                    let marker = if index == self.state.instruction_index { '>' } else { ' ' };
+                    let breakpoint = if self.breakpoints.contains(&index) { '*' } else { ' ' };
+                    let visited = if self.state.visited_instructions.contains(&index) { 'v' } else { ' ' };
+                    println!("{}{}{} #{}: {}", marker, breakpoint, visited, index, self.program.get_instruction(index).unwrap());
+                }
+            },
+            Command::Reset => {
+                self.state = ExecutionState::new();
+                self.finished = false;
+                self.print_current();
+            },
+            Command::Help => {
+                println!("Commands: step [n] (s), continue (c), break <index> (b), delete <index> (d), input <value> (i), watch (w), unwatch, print (p), visited (v), list (l), reset (r), help (h), quit (q)");
+            },
+            Command::Quit => return false,
+        }
+
+        true
+    }
+
+    pub fn run_interactive<R: BufRead>(&mut self, input: R) {
+        self.print_current();
+        for line in input.lines() {
+            let line = line.unwrap();
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match line.parse::<Command>() {
+                Ok(command) => {
+                    if !self.execute(command) {
+                        break;
+                    }
+                },
+                Err(e) => println!("Error: {}", e),
+            }
+        }
+    }
+}
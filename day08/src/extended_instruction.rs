@@ -0,0 +1,185 @@
+use std::error::Error;
+use std::str::FromStr;
+
+use crate::bytecode::{Bytecode, BytecodeReader, BytecodeWriter};
+use crate::instruction::{Instruction, OPCODE_ACCUMULATE, OPCODE_JUMP, OPCODE_NO_OP};
+use crate::instruction_set::{Effect, InstructionSet};
+use crate::parse_error::{split_tokens, ParseErrorKind, TokenError};
+use crate::program::ExecutionState;
+use crate::register::{Operand, Register};
+
+// superset of the boot code instructions, so that any boot program is also a valid extended program
+#[derive(Clone, Copy)]
+pub enum ExtendedInstruction {
+    Basic(Instruction),
+    Set(Register, Operand),
+    Add(Register, Operand),
+    Subtract(Register, Operand),
+    Multiply(Register, Operand),
+    JumpIfZero(Operand, Operand),
+    JumpIfNotZero(Operand, Operand),
+    Input(Register),
+    Output(Operand),
+}
+
+const MNEMONICS: [&str; 11] = ["acc", "jmp", "nop", "set", "add", "sub", "mul", "jz", "jnz", "in", "out"];
+
+impl FromStr for ExtendedInstruction {
+    type Err = TokenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = split_tokens(s);
+        let parts: Vec<_> = tokens.iter().map(|&(_, token)| token).collect();
+        let register = |i: usize| parts[i].parse::<Register>().map_err(|e| e.at_column(tokens[i].0));
+        let operand = |i: usize| parts[i].parse::<Operand>().map_err(|e| e.at_column(tokens[i].0));
+
+        match parts.as_slice() {
+            ["acc", _] | ["jmp", _] | ["nop", _] => Ok(Self::Basic(s.parse()?)),
+            ["set", _, _] => Ok(Self::Set(register(1)?, operand(2)?)),
+            ["add", _, _] => Ok(Self::Add(register(1)?, operand(2)?)),
+            ["sub", _, _] => Ok(Self::Subtract(register(1)?, operand(2)?)),
+            ["mul", _, _] => Ok(Self::Multiply(register(1)?, operand(2)?)),
+            ["jz", _, _] => Ok(Self::JumpIfZero(operand(1)?, operand(2)?)),
+            ["jnz", _, _] => Ok(Self::JumpIfNotZero(operand(1)?, operand(2)?)),
+            ["in", _] => Ok(Self::Input(register(1)?)),
+            ["out", _] => Ok(Self::Output(operand(1)?)),
+            [command, ..] if MNEMONICS.contains(command) => Err(TokenError::new(1, s, ParseErrorKind::WrongFormat)),
+            [command, ..] => Err(TokenError::new(tokens[0].0, command, ParseErrorKind::UnknownCommand)),
+            [] => Err(TokenError::new(1, s, ParseErrorKind::WrongFormat)),
+        }
+    }
+}
+
+impl InstructionSet for ExtendedInstruction {
+    const INDEX_DETERMINES_FLOW: bool = false;
+
+    fn get_jump_offset(&self) -> Option<i64> {
+        match *self {
+            Self::Basic(instruction) => instruction.get_jump_offset(),
+            Self::JumpIfZero(_, Operand::Immediate(offset)) | Self::JumpIfNotZero(_, Operand::Immediate(offset)) => Some(offset),
+            _ => None,
+        }
+    }
+
+    fn get_patched(&self) -> Self {
+        match *self {
+            Self::Basic(instruction) => Self::Basic(instruction.get_patched()),
+            other => other,
+        }
+    }
+
+    // arithmetic wraps around on overflow
+    fn execute(&self, state: &mut ExecutionState) -> Effect {
+        match *self {
+            Self::Basic(instruction) => instruction.execute(state),
+            Self::Set(register, operand) => {
+                state.set_register(register, state.get_operand(operand));
+                Effect::Continue
+            },
+            Self::Add(register, operand) => {
+                state.set_register(register, state.get_register(register).wrapping_add(state.get_operand(operand)));
+                Effect::Continue
+            },
+            Self::Subtract(register, operand) => {
+                state.set_register(register, state.get_register(register).wrapping_sub(state.get_operand(operand)));
+                Effect::Continue
+            },
+            Self::Multiply(register, operand) => {
+                state.set_register(register, state.get_register(register).wrapping_mul(state.get_operand(operand)));
+                Effect::Continue
+            },
+            Self::JumpIfZero(condition, offset) => {
+                if state.get_operand(condition) == 0 { Effect::Jump(state.get_operand(offset)) } else { Effect::Continue }
+            },
+            Self::JumpIfNotZero(condition, offset) => {
+                if state.get_operand(condition) != 0 { Effect::Jump(state.get_operand(offset)) } else { Effect::Continue }
+            },
+            Self::Input(register) => match state.input.pop_front() {
+                Some(value) => {
+                    state.set_register(register, value);
+                    Effect::Continue
+                },
+                None => Effect::NeedsInput,
+            },
+            Self::Output(operand) => {
+                state.output.push(state.get_operand(operand));
+                Effect::Continue
+            },
+        }
+    }
+}
+
+// opcodes of the basic instructions are shared, so that boot bytecode is also valid extended bytecode
+const OPCODE_SET: u8 = 3;
+const OPCODE_ADD: u8 = 4;
+const OPCODE_SUBTRACT: u8 = 5;
+const OPCODE_MULTIPLY: u8 = 6;
+const OPCODE_JUMP_IF_ZERO: u8 = 7;
+const OPCODE_JUMP_IF_NOT_ZERO: u8 = 8;
+const OPCODE_INPUT: u8 = 9;
+const OPCODE_OUTPUT: u8 = 10;
+
+impl Bytecode for ExtendedInstruction {
+    fn encode(&self, writer: &mut BytecodeWriter) {
+        match *self {
+            Self::Basic(instruction) => instruction.encode(writer),
+            Self::Set(register, operand) | Self::Add(register, operand) | Self::Subtract(register, operand) | Self::Multiply(register, operand) => {
+                writer.write_opcode(match self {
+                    Self::Set(..) => OPCODE_SET,
+                    Self::Add(..) => OPCODE_ADD,
+                    Self::Subtract(..) => OPCODE_SUBTRACT,
+                    _ => OPCODE_MULTIPLY,
+                });
+                writer.write_register(register);
+                writer.write_operand(operand);
+            },
+            Self::JumpIfZero(condition, offset) | Self::JumpIfNotZero(condition, offset) => {
+                writer.write_opcode(match self {
+                    Self::JumpIfZero(..) => OPCODE_JUMP_IF_ZERO,
+                    _ => OPCODE_JUMP_IF_NOT_ZERO,
+                });
+                writer.write_operand(condition);
+                writer.write_operand(offset);
+            },
+            Self::Input(register) => {
+                writer.write_opcode(OPCODE_INPUT);
+                writer.write_register(register);
+            },
+            Self::Output(operand) => {
+                writer.write_opcode(OPCODE_OUTPUT);
+                writer.write_operand(operand);
+            },
+        }
+    }
+
+    fn decode(opcode: u8, reader: &mut BytecodeReader) -> Result<Self, Box<dyn Error>> {
+        match opcode {
+            OPCODE_ACCUMULATE | OPCODE_JUMP | OPCODE_NO_OP => Ok(Self::Basic(Instruction::decode(opcode, reader)?)),
+            OPCODE_SET => Ok(Self::Set(reader.read_register()?, reader.read_operand()?)),
+            OPCODE_ADD => Ok(Self::Add(reader.read_register()?, reader.read_operand()?)),
+            OPCODE_SUBTRACT => Ok(Self::Subtract(reader.read_register()?, reader.read_operand()?)),
+            OPCODE_MULTIPLY => Ok(Self::Multiply(reader.read_register()?, reader.read_operand()?)),
+            OPCODE_JUMP_IF_ZERO => Ok(Self::JumpIfZero(reader.read_operand()?, reader.read_operand()?)),
+            OPCODE_JUMP_IF_NOT_ZERO => Ok(Self::JumpIfNotZero(reader.read_operand()?, reader.read_operand()?)),
+            OPCODE_INPUT => Ok(Self::Input(reader.read_register()?)),
+            OPCODE_OUTPUT => Ok(Self::Output(reader.read_operand()?)),
+            _ => Err(reader.get_unknown_opcode_error(opcode)),
+        }
+    }
+}
+
+impl std::fmt::Display for ExtendedInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Basic(instruction) => write!(f, "{}", instruction),
+            Self::Set(register, operand) => write!(f, "set {} {}", register, operand),
+            Self::Add(register, operand) => write!(f, "add {} {}", register, operand),
+            Self::Subtract(register, operand) => write!(f, "sub {} {}", register, operand),
+            Self::Multiply(register, operand) => write!(f, "mul {} {}", register, operand),
+            Self::JumpIfZero(condition, offset) => write!(f, "jz {} {}", condition, offset),
+            Self::JumpIfNotZero(condition, offset) => write!(f, "jnz {} {}", condition, offset),
+            Self::Input(register) => write!(f, "in {}", register),
+            Self::Output(operand) => write!(f, "out {}", operand),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use std::error::Error;
+use std::str::FromStr;
+
+use crate::bytecode::{Bytecode, BytecodeReader, BytecodeWriter};
+use crate::instruction_set::{Effect, InstructionSet};
+use crate::parse_error::{parse_number, split_tokens, ParseErrorKind, TokenError};
+use crate::program::ExecutionState;
+
+#[derive(Clone, Copy)]
+pub enum Instruction {
+    Accumulate(i64),
+    Jump(i64),
+    NoOp(i64),
+}
+
+impl FromStr for Instruction {
+    type Err = TokenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = split_tokens(s);
+        let argument = || parse_number(tokens[1].1, tokens[1].0);
+
+        match tokens.as_slice() {
+            [(_, "acc"), _] => Ok(Self::Accumulate(argument()?)),
+            [(_, "jmp"), _] => Ok(Self::Jump(argument()?)),
+            [(_, "nop"), _] => Ok(Self::NoOp(argument()?)),
+            [(_, "acc"), ..] | [(_, "jmp"), ..] | [(_, "nop"), ..] | [] => Err(TokenError::new(1, s, ParseErrorKind::WrongFormat)),
+            [(column, command), ..] => Err(TokenError::new(*column, command, ParseErrorKind::UnknownCommand)),
+        }
+    }
+}
+
+impl InstructionSet for Instruction {
+    const INDEX_DETERMINES_FLOW: bool = true;
+
+    fn get_jump_offset(&self) -> Option<i64> {
+        match *self {
+            Self::Jump(offset) => Some(offset),
+            _ => None,
+        }
+    }
+
+    fn get_patched(&self) -> Self {
+        match *self {
+            Self::Accumulate(value) => Self::Accumulate(value),
+            Self::Jump(ignore) => Self::NoOp(ignore),
+            Self::NoOp(offset) => Self::Jump(offset),
+        }
+    }
+
+    fn execute(&self, state: &mut ExecutionState) -> Effect {
+        match *self {
+            Self::Accumulate(value) => {
+                state.accumulator = state.accumulator.wrapping_add(value);
+                Effect::Continue
+            },
+            Self::Jump(offset) => Effect::Jump(offset),
+            Self::NoOp(_) => Effect::Continue,
+        }
+    }
+}
+
+pub const OPCODE_ACCUMULATE: u8 = 0;
+pub const OPCODE_JUMP: u8 = 1;
+pub const OPCODE_NO_OP: u8 = 2;
+
+impl Bytecode for Instruction {
+    fn encode(&self, writer: &mut BytecodeWriter) {
+        let (opcode, value) = match *self {
+            Self::Accumulate(value) => (OPCODE_ACCUMULATE, value),
+            Self::Jump(offset) => (OPCODE_JUMP, offset),
+            Self::NoOp(value) => (OPCODE_NO_OP, value),
+        };
+        writer.write_opcode(opcode);
+        writer.write_value(value);
+    }
+
+    fn decode(opcode: u8, reader: &mut BytecodeReader) -> Result<Self, Box<dyn Error>> {
+        match opcode {
+            OPCODE_ACCUMULATE => Ok(Self::Accumulate(reader.read_value()?)),
+            OPCODE_JUMP => Ok(Self::Jump(reader.read_value()?)),
+            OPCODE_NO_OP => Ok(Self::NoOp(reader.read_value()?)),
+            _ => Err(reader.get_unknown_opcode_error(opcode)),
+        }
+    }
+}
+
+impl std::fmt::Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Accumulate(value) => write!(f, "acc {:+}", value),
+            Self::Jump(offset) => write!(f, "jmp {:+}", offset),
+            Self::NoOp(value) => write!(f, "nop {:+}", value),
+        }
+    }
+}
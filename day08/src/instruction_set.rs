@@ -0,0 +1,24 @@
+use std::fmt::Display;
+
+use crate::program::ExecutionState;
+
+pub enum Effect {
+    Continue,
+    Jump(i64),
+    NeedsInput,
+}
+
+pub trait InstructionSet: Copy + Display {
+    // whether the next instruction index only depends on the current one,
+    // so that visiting the same instruction twice means that the program loops
+    const INDEX_DETERMINES_FLOW: bool;
+
+    // statically known relative jump target, used to validate programs at load time
+    fn get_jump_offset(&self) -> Option<i64>;
+
+    // instruction to execute instead of this one when it is the overridden one
+    fn get_patched(&self) -> Self;
+
+    // should not change the state when returning Effect::NeedsInput
+    fn execute(&self, state: &mut ExecutionState) -> Effect;
+}
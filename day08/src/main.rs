@@ -1,120 +1,210 @@
-use std::collections::HashSet;
-use std::io::{self, BufRead};
+use std::env;
 use std::error::Error;
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, Read};
+use std::process;
 use std::str::FromStr;
 
 #[macro_use] extern crate lazy_static;
-use regex::Regex;
 
-#[derive(Clone, Copy)]
-enum Instruction {
-    Accumulate(i64),
-    Jump(i64),
-    NoOp(i64),
+mod analysis;
+mod assembler;
+mod bytecode;
+mod debugger;
+mod extended_instruction;
+mod instruction;
+mod instruction_set;
+mod parse_error;
+mod program;
+mod register;
+mod trace;
+
+use analysis::ControlFlowGraph;
+use bytecode::Bytecode;
+use debugger::Debugger;
+use extended_instruction::ExtendedInstruction;
+use instruction::Instruction;
+use instruction_set::InstructionSet;
+use parse_error::TokenError;
+use program::{ExecutionState, Program, ProgramResult};
+use trace::Trace;
+
+const MAX_STEPS: usize = 1_000_000;
+
+fn exit_on_error<T, E: Display>(result: Result<T, E>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        },
+    }
 }
 
-impl FromStr for Instruction {
-    type Err = Box<dyn Error>;
+fn read_instructions<R: Read>(mut input: R) -> Result<Vec<Instruction>, Box<dyn Error>> {
+    let mut source = String::new();
+    input.read_to_string(&mut source)?;
+    Ok(assembler::assemble(&source)?)
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        lazy_static! {
-            static ref LINE_RE: Regex = Regex::new(r"^(\w+)\s+([+-]?\d+)$").unwrap();
+// accepts both bytecode and assembler source
+fn load_program<I: FromStr<Err = TokenError> + InstructionSet + Bytecode>(path: &str) -> Vec<I> {
+    let load = || -> Result<Vec<I>, Box<dyn Error>> {
+        let bytes = fs::read(path)?;
+        if bytecode::is_bytecode(&bytes) {
+            let instructions = bytecode::decode_program(&bytes)?;
+            assembler::validate_jumps(&instructions)?;
+            Ok(instructions)
+        } else {
+            Ok(assembler::assemble(&String::from_utf8(bytes)?)?)
         }
+    };
 
-        match LINE_RE.captures(s) {
-            Some(captures) => {
-                match captures[1].as_ref() {
-                    "acc" => Ok(Self::Accumulate(captures[2].parse()?)),
-                    "jmp" => Ok(Self::Jump(captures[2].parse()?)),
-                    "nop" => Ok(Self::NoOp(captures[2].parse()?)),
-                    _ => Err(Box::from("wrong command")),
-                }
-            },
-            _ => Err(Box::from("wrong string format"))
-        }
+    exit_on_error(load())
+}
+
+fn solve(instructions: &[Instruction]) {
+    let program = Program::new(instructions, -1);
+    match program.run() {
+        ProgramResult::Looped(accumulator) => println!("Original program looped: {}", accumulator),
+        ProgramResult::Terminated(accumulator) => println!("Original program terminated: {}", accumulator),
+        ProgramResult::WaitingForInput(_) => unreachable!(),
+    }
+
+    match ControlFlowGraph::new(instructions).find_patch() {
+        Some(patch) => println!("Program #{} terminated: {}", patch.index, patch.accumulator),
+        None => println!("No single instruction patch makes the program terminate"),
     }
 }
 
-enum ProgramResult {
-    Terminated(i64),
-    Looped(i64),
+// splits arguments into "--flags" and positional ones
+fn split_flags(args: &[String]) -> (Vec<&str>, Vec<&str>) {
+    let (flags, positional): (Vec<_>, Vec<_>) = args.iter().map(|arg| arg.as_str()).partition(|arg| arg.starts_with("--"));
+    (flags, positional)
 }
 
-struct Program<'a> {
-    instructions: &'a[Instruction],
-    override_index: i64,
+fn parse_override_index(arg: Option<&&str>) -> i64 {
+    match arg {
+        Some(index) => index.parse().unwrap(),
+        None => -1,
+    }
 }
 
-impl<'a> Program<'a> {
-    pub fn new(instructions: &'a [Instruction], override_index: i64) -> Self {
-        Program {
-            instructions,
-            override_index,
-        }
+fn debug_program<I: InstructionSet>(instructions: &[I], override_index: i64) {
+    let mut debugger = Debugger::new(Program::new(instructions, override_index));
+    let stdin = io::stdin();
+    debugger.run_interactive(stdin.lock());
+}
+
+// usage: day08 debug [--extended] <program or bytecode file> [override index]; debugger commands are read from stdin
+fn debug(args: &[String]) {
+    let (flags, args) = split_flags(args);
+    let override_index = parse_override_index(args.get(1));
+
+    if flags.contains(&"--extended") {
+        debug_program(&load_program::<ExtendedInstruction>(args[0]), override_index);
+    } else {
+        debug_program(&load_program::<Instruction>(args[0]), override_index);
     }
+}
 
-    fn get_instruction(&self, index: i64) -> Option<Instruction> {
-        if index < 0 || (index as usize) >= self.instructions.len() {
-            return None;
-        }
+// usage: day08 run <program or bytecode file> [input values...]; runs the program with the extended instruction set
+fn run(args: &[String]) {
+    let instructions = load_program::<ExtendedInstruction>(&args[0]);
+    let mut state = ExecutionState::new();
+    state.input = args[1..].iter().map(|value| value.parse().unwrap()).collect();
 
-        if index != self.override_index {
-            return Some(self.instructions[index as usize]);
-        }
+    let result = Program::new(&instructions, -1).run_with_limit(&mut state, MAX_STEPS);
+    for value in &state.output {
+        println!("Output: {}", value);
+    }
 
-        match self.instructions[index as usize] {
-            Instruction::Accumulate(value) => Some(Instruction::Accumulate(value)),
-            Instruction::Jump(ignore) => Some(Instruction::NoOp(ignore)),
-            Instruction::NoOp(offset) => Some(Instruction::Jump(offset)),
-        }
+    match result {
+        Some(ProgramResult::Looped(accumulator)) => println!("Program looped: {}", accumulator),
+        Some(ProgramResult::Terminated(accumulator)) => println!("Program terminated: {}", accumulator),
+        Some(ProgramResult::WaitingForInput(accumulator)) => println!("Program ran out of input: {}", accumulator),
+        None => println!("Program did not finish in {} steps: {}", MAX_STEPS, state.accumulator),
     }
+}
 
-    pub fn run(&self) -> ProgramResult {
-        let mut current_instruction_index = 0;
-        let mut visited_instructions = HashSet::new();
-        let mut accumulator = 0;
-        loop {
-            if visited_instructions.contains(&current_instruction_index) {
-                return ProgramResult::Looped(accumulator);
-            }
-    
-            visited_instructions.insert(current_instruction_index);
-    
-            match self.get_instruction(current_instruction_index) {
-                Some(Instruction::Accumulate(value)) => {
-                    accumulator += value;
-                },
-                Some(Instruction::Jump(offset)) => {
-                    current_instruction_index += offset-1;
-                }
-                Some(Instruction::NoOp(_)) => {},
-                None => {
-                    return ProgramResult::Terminated(accumulator);
-                }
-            }
-            
-            current_instruction_index += 1;
-        }
-        }
+// input values are passed as --input=1,2,3
+fn parse_input_flag(flags: &[&str]) -> Vec<i64> {
+    match flags.iter().find_map(|flag| flag.strip_prefix("--input=")) {
+        Some(values) => values.split(',').map(|value| value.parse().unwrap()).collect(),
+        None => vec![],
+    }
 }
 
-fn main() {
-    let stdin = io::stdin();
-    let instructions: Vec<_> = stdin.lock().lines().into_iter()
-        .map(|line| line.unwrap().parse::<Instruction>().unwrap())
-        .collect();
+fn trace_program<I: InstructionSet>(instructions: &[I], override_index: i64, input: &[i64]) -> Trace {
+    let mut state = ExecutionState::new();
+    state.input = input.iter().cloned().collect();
+    Trace::record(&Program::new(instructions, override_index), &mut state, MAX_STEPS)
+}
 
-    let program = Program::new(&instructions, -1);
-    match program.run() {
-        ProgramResult::Looped(accumulator) => println!("Original program looped: {}", accumulator),
-        ProgramResult::Terminated(accumulator) => println!("Original program terminated: {}", accumulator),
+fn trace_file(path: &str, override_index: i64, flags: &[&str]) -> Trace {
+    let input = parse_input_flag(flags);
+    if flags.contains(&"--extended") {
+        trace_program(&load_program::<ExtendedInstruction>(path), override_index, &input)
+    } else {
+        trace_program(&load_program::<Instruction>(path), override_index, &input)
     }
+}
 
-    for i in 0..instructions.len() {
-        let program = Program::new(&instructions, i as i64);
-        match program.run() {
-            ProgramResult::Looped(_) => {},
-            ProgramResult::Terminated(accumulator) => println!("Program #{} terminated: {}", i, accumulator),
-        }
+// usage: day08 trace [--extended] [--input=values] [--profile | --json] <program or bytecode file> [override index]
+// prints the execution trace as CSV, per-instruction hit counts as CSV with --profile, or both as JSON with --json
+fn trace(args: &[String]) {
+    let (flags, args) = split_flags(args);
+    let trace = trace_file(args[0], parse_override_index(args.get(1)), &flags);
+
+    if flags.contains(&"--json") {
+        print!("{}", trace.to_json());
+    } else if flags.contains(&"--profile") {
+        print!("{}", trace.get_profile_csv());
+    } else {
+        print!("{}", trace.to_csv());
+    }
+}
+
+// usage: day08 trace-diff [--extended] [--input=values] <program or bytecode file> <override index> <other override index>
+fn trace_diff(args: &[String]) {
+    let (flags, args) = split_flags(args);
+    let trace = trace_file(args[0], parse_override_index(args.get(1)), &flags);
+    let other_trace = trace_file(args[0], parse_override_index(args.get(2)), &flags);
+
+    trace.print_summary();
+    other_trace.print_summary();
+    match trace.find_divergence(&other_trace) {
+        Some(step) => println!("Traces diverge at step {}: {} vs {}", step, trace.describe_step(step), other_trace.describe_step(step)),
+        None => println!("Traces are identical"),
+    }
+}
+
+// usage: day08 assemble <program file> <bytecode file>
+fn assemble(args: &[String]) {
+    let instructions = load_program::<ExtendedInstruction>(&args[0]);
+    fs::write(&args[1], bytecode::encode_program(&instructions)).unwrap();
+    println!("Assembled {} instructions", instructions.len());
+}
+
+// usage: day08 disassemble <bytecode file>; prints the program in text format
+fn disassemble(args: &[String]) {
+    let instructions = load_program::<ExtendedInstruction>(&args[0]);
+    print!("{}", assembler::disassemble(&instructions));
+}
+
+fn main() {
+    let args: Vec<_> = env::args().skip(1).collect();
+    match args.first().map(|arg| arg.as_str()) {
+        Some("debug") => debug(&args[1..]),
+        Some("run") => run(&args[1..]),
+        Some("trace") => trace(&args[1..]),
+        Some("trace-diff") => trace_diff(&args[1..]),
+        Some("assemble") => assemble(&args[1..]),
+        Some("disassemble") => disassemble(&args[1..]),
+        _ => {
+            let stdin = io::stdin();
+            solve(&exit_on_error(read_instructions(stdin.lock())));
+        },
     }
 }
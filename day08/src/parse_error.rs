@@ -0,0 +1,123 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum ParseErrorKind {
+    WrongFormat,
+    UnknownCommand,
+    WrongNumber,
+    WrongRegister,
+    UnknownLabel,
+    DuplicateLabel,
+    JumpOutOfRange { target: i64, instructions_count: usize },
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::WrongFormat => write!(f, "wrong string format"),
+            Self::UnknownCommand => write!(f, "unknown command"),
+            Self::WrongNumber => write!(f, "wrong number"),
+            Self::WrongRegister => write!(f, "wrong register name"),
+            Self::UnknownLabel => write!(f, "unknown label"),
+            Self::DuplicateLabel => write!(f, "duplicate label"),
+            Self::JumpOutOfRange { target, instructions_count } => write!(f, "jump target #{} is outside of the program (0..={})", target, instructions_count),
+        }
+    }
+}
+
+// error within a single instruction; column is 1-based and relative to the instruction string
+#[derive(Debug)]
+pub struct TokenError {
+    pub column: usize,
+    pub token: String,
+    pub kind: ParseErrorKind,
+}
+
+impl TokenError {
+    pub fn new(column: usize, token: &str, kind: ParseErrorKind) -> Self {
+        TokenError {
+            column,
+            token: token.to_owned(),
+            kind,
+        }
+    }
+
+    // for errors of nested parsers, which only know about their own token
+    pub fn at_column(self, column: usize) -> Self {
+        TokenError {
+            column,
+            ..self
+        }
+    }
+
+    pub fn at_line(self, line: usize, column_offset: usize) -> ParseError {
+        ParseError {
+            line,
+            column: self.column + column_offset,
+            token: self.token,
+            kind: self.kind,
+        }
+    }
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "column {}: {} '{}'", self.column, self.kind, self.token)
+    }
+}
+
+impl Error for TokenError {}
+
+// line and column are 1-based
+#[derive(Debug)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub token: String,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: {} '{}'", self.line, self.column, self.kind, self.token)
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Debug)]
+pub struct ParseErrors(pub Vec<ParseError>);
+
+impl fmt::Display for ParseErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for error in &self.0 {
+            writeln!(f, "{}", error)?;
+        }
+        write!(f, "{} error(s) found", self.0.len())
+    }
+}
+
+impl Error for ParseErrors {}
+
+pub fn parse_number(token: &str, column: usize) -> Result<i64, TokenError> {
+    token.parse().map_err(|_| TokenError::new(column, token, ParseErrorKind::WrongNumber))
+}
+
+// whitespace-separated tokens with their 1-based columns
+pub fn split_tokens(s: &str) -> Vec<(usize, &str)> {
+    let mut tokens = vec![];
+    let mut token_start = None;
+    for (position, ch) in s.char_indices().chain(std::iter::once((s.len(), ' '))) {
+        match (token_start, ch.is_whitespace()) {
+            (None, false) => token_start = Some(position),
+            (Some(start), true) => {
+                tokens.push((start + 1, &s[start..position]));
+                token_start = None;
+            },
+            _ => {},
+        }
+    }
+
+    tokens
+}
@@ -0,0 +1,204 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::instruction_set::{Effect, InstructionSet};
+use crate::register::{NAMED_REGISTERS_COUNT, Operand, Register};
+
+pub enum ProgramResult {
+    Terminated(i64),
+    Looped(i64),
+    WaitingForInput(i64),
+}
+
+pub enum StepResult {
+    Running,
+    Terminated(i64),
+    Looped(i64),
+    WaitingForInput(i64),
+}
+
+impl StepResult {
+    fn get_program_result(self) -> Option<ProgramResult> {
+        match self {
+            Self::Running => None,
+            Self::Terminated(accumulator) => Some(ProgramResult::Terminated(accumulator)),
+            Self::Looped(accumulator) => Some(ProgramResult::Looped(accumulator)),
+            Self::WaitingForInput(accumulator) => Some(ProgramResult::WaitingForInput(accumulator)),
+        }
+    }
+}
+
+struct MachineSnapshot {
+    instruction_index: i64,
+    accumulator: i64,
+    registers: [i64; NAMED_REGISTERS_COUNT],
+    input: VecDeque<i64>,
+}
+
+pub struct ExecutionState {
+    pub instruction_index: i64,
+    pub accumulator: i64,
+    pub registers: [i64; NAMED_REGISTERS_COUNT],
+    pub input: VecDeque<i64>,
+    pub output: Vec<i64>,
+    pub visited_instructions: HashSet<i64>,
+    // Brent's cycle detection: the state is saved after a power of two steps, and compared with the following ones
+    saved_snapshot: Option<MachineSnapshot>,
+    steps_since_snapshot: usize,
+    snapshot_interval: usize,
+}
+
+impl ExecutionState {
+    pub fn new() -> Self {
+        ExecutionState {
+            instruction_index: 0,
+            accumulator: 0,
+            registers: [0; NAMED_REGISTERS_COUNT],
+            input: VecDeque::new(),
+            output: vec![],
+            visited_instructions: HashSet::new(),
+            saved_snapshot: None,
+            steps_since_snapshot: 0,
+            snapshot_interval: 1,
+        }
+    }
+
+    pub fn get_register(&self, register: Register) -> i64 {
+        match register {
+            Register::Accumulator => self.accumulator,
+            Register::Named(number) => self.registers[number as usize],
+        }
+    }
+
+    pub fn set_register(&mut self, register: Register, value: i64) {
+        match register {
+            Register::Accumulator => self.accumulator = value,
+            Register::Named(number) => self.registers[number as usize] = value,
+        }
+    }
+
+    pub fn get_operand(&self, operand: Operand) -> i64 {
+        match operand {
+            Operand::Register(register) => self.get_register(register),
+            Operand::Immediate(value) => value,
+        }
+    }
+
+    fn get_snapshot(&self) -> MachineSnapshot {
+        MachineSnapshot {
+            instruction_index: self.instruction_index,
+            accumulator: self.accumulator,
+            registers: self.registers,
+            input: self.input.clone(),
+        }
+    }
+
+    fn matches_snapshot(&self, snapshot: &MachineSnapshot) -> bool {
+        self.instruction_index == snapshot.instruction_index
+            && self.accumulator == snapshot.accumulator
+            && self.registers == snapshot.registers
+            && self.input == snapshot.input
+    }
+
+    // whether the current state was already seen since the last saved one, which means the program loops
+    fn is_looping(&self) -> bool {
+        self.steps_since_snapshot > 0 && matches!(&self.saved_snapshot, Some(snapshot) if self.matches_snapshot(snapshot))
+    }
+
+    fn record_step(&mut self) {
+        self.steps_since_snapshot += 1;
+        if self.steps_since_snapshot >= self.snapshot_interval {
+            self.saved_snapshot = Some(self.get_snapshot());
+            self.steps_since_snapshot = 0;
+            self.snapshot_interval *= 2;
+        }
+    }
+}
+
+pub struct Program<'a, I: InstructionSet> {
+    instructions: &'a[I],
+    override_index: i64,
+}
+
+impl<'a, I: InstructionSet> Program<'a, I> {
+    pub fn new(instructions: &'a [I], override_index: i64) -> Self {
+        Program {
+            instructions,
+            override_index,
+        }
+    }
+
+    pub fn get_instructions_count(&self) -> usize {
+        self.instructions.len()
+    }
+
+    pub fn get_instruction(&self, index: i64) -> Option<I> {
+        if index < 0 || (index as usize) >= self.instructions.len() {
+            return None;
+        }
+
+        if index != self.override_index {
+            return Some(self.instructions[index as usize]);
+        }
+
+        Some(self.instructions[index as usize].get_patched())
+    }
+
+    // executes a single instruction, unless the program loops, waits for input or is out of program
+    pub fn step(&self, state: &mut ExecutionState) -> StepResult {
+        if I::INDEX_DETERMINES_FLOW {
+            if state.visited_instructions.contains(&state.instruction_index) {
+                return StepResult::Looped(state.accumulator);
+            }
+        } else if state.is_looping() {
+            return StepResult::Looped(state.accumulator);
+        }
+
+        match self.get_instruction(state.instruction_index) {
+            Some(instruction) => {
+                let index = state.instruction_index;
+                match instruction.execute(state) {
+                    Effect::Continue => {
+                        state.instruction_index += 1;
+                    },
+                    Effect::Jump(offset) => {
+                        state.instruction_index = state.instruction_index.wrapping_add(offset);
+                    },
+                    Effect::NeedsInput => {
+                        return StepResult::WaitingForInput(state.accumulator);
+                    },
+                }
+
+                state.visited_instructions.insert(index);
+                if !I::INDEX_DETERMINES_FLOW {
+                    state.record_step();
+                }
+
+                StepResult::Running
+            },
+            None => StepResult::Terminated(state.accumulator),
+        }
+    }
+
+    pub fn run_with_state(&self, state: &mut ExecutionState) -> ProgramResult {
+        loop {
+            if let Some(result) = self.step(state).get_program_result() {
+                return result;
+            }
+        }
+    }
+
+    // None if the program is still running after max_steps steps
+    pub fn run_with_limit(&self, state: &mut ExecutionState, max_steps: usize) -> Option<ProgramResult> {
+        for _ in 0..max_steps {
+            if let Some(result) = self.step(state).get_program_result() {
+                return Some(result);
+            }
+        }
+
+        None
+    }
+
+    pub fn run(&self) -> ProgramResult {
+        self.run_with_state(&mut ExecutionState::new())
+    }
+}
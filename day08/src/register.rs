@@ -0,0 +1,59 @@
+use std::str::FromStr;
+
+use crate::parse_error::{parse_number, ParseErrorKind, TokenError};
+
+pub const NAMED_REGISTERS_COUNT: usize = 26;
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum Register {
+    Accumulator,
+    Named(u8), // a..z
+}
+
+impl FromStr for Register {
+    type Err = TokenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = s.as_bytes();
+        match bytes {
+            b"acc" => Ok(Self::Accumulator),
+            [ch] if ch.is_ascii_lowercase() => Ok(Self::Named(ch - b'a')),
+            _ => Err(TokenError::new(1, s, ParseErrorKind::WrongRegister)),
+        }
+    }
+}
+
+impl std::fmt::Display for Register {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Accumulator => write!(f, "acc"),
+            Self::Named(number) => write!(f, "{}", (b'a' + number) as char),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Operand {
+    Register(Register),
+    Immediate(i64),
+}
+
+impl FromStr for Operand {
+    type Err = TokenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.as_bytes().first() {
+            Some(b'+') | Some(b'-') | Some(b'0'..=b'9') => Ok(Self::Immediate(parse_number(s, 1)?)),
+            _ => Ok(Self::Register(s.parse()?)),
+        }
+    }
+}
+
+impl std::fmt::Display for Operand {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Register(register) => write!(f, "{}", register),
+            Self::Immediate(value) => write!(f, "{:+}", value),
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use crate::instruction_set::InstructionSet;
+use crate::program::{ExecutionState, Program, ProgramResult, StepResult};
+
+pub struct TraceEntry {
+    pub instruction_index: i64,
+    pub instruction: String,
+    pub accumulator_before: i64,
+    pub accumulator_after: i64,
+}
+
+pub struct Trace {
+    pub entries: Vec<TraceEntry>,
+    pub hit_counts: Vec<usize>,
+    pub instructions: Vec<String>,
+    // None if the program was stopped because of the steps limit
+    pub result: Option<ProgramResult>,
+    pub final_accumulator: i64,
+}
+
+impl Trace {
+    pub fn record<I: InstructionSet>(program: &Program<I>, state: &mut ExecutionState, max_steps: usize) -> Self {
+        let instructions_count = program.get_instructions_count();
+        let mut entries = vec![];
+        let mut hit_counts = vec![0; instructions_count];
+        let mut result = None;
+        for _ in 0..max_steps {
+            let instruction_index = state.instruction_index;
+            let accumulator_before = state.accumulator;
+            match program.step(state) {
+                StepResult::Running => {
+                    hit_counts[instruction_index as usize] += 1;
+                    entries.push(TraceEntry {
+                        instruction_index,
+                        instruction: program.get_instruction(instruction_index).unwrap().to_string(),
+                        accumulator_before,
+                        accumulator_after: state.accumulator,
+                    });
+                },
+                StepResult::Terminated(accumulator) => {
+                    result = Some(ProgramResult::Terminated(accumulator));
+                    break;
+                },
+                StepResult::Looped(accumulator) => {
+                    result = Some(ProgramResult::Looped(accumulator));
+                    break;
+                },
+                StepResult::WaitingForInput(accumulator) => {
+                    result = Some(ProgramResult::WaitingForInput(accumulator));
+                    break;
+                },
+            }
+        }
+
+        Trace {
+            entries,
+            hit_counts,
+            instructions: (0..instructions_count as i64).map(|index| program.get_instruction(index).unwrap().to_string()).collect(),
+            result,
+            final_accumulator: state.accumulator,
+        }
+    }
+
+    fn get_result_name(&self) -> &'static str {
+        match self.result {
+            Some(ProgramResult::Terminated(_)) => "terminated",
+            Some(ProgramResult::Looped(_)) => "looped",
+            Some(ProgramResult::WaitingForInput(_)) => "waiting_for_input",
+            None => "steps_limit",
+        }
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut result = String::from("step,index,instruction,accumulator_before,accumulator_after\n");
+        for (step, entry) in self.entries.iter().enumerate() {
+            result += &format!("{},{},{},{},{}\n", step, entry.instruction_index, entry.instruction, entry.accumulator_before, entry.accumulator_after);
+        }
+
+        result
+    }
+
+    pub fn get_profile_csv(&self) -> String {
+        let mut result = String::from("index,instruction,hits\n");
+        for (index, hits) in self.hit_counts.iter().enumerate() {
+            result += &format!("{},{},{}\n", index, self.instructions[index], hits);
+        }
+
+        result
+    }
+
+    // instructions only consist of mnemonics, register names and numbers, so nothing needs escaping
+    pub fn to_json(&self) -> String {
+        let steps: Vec<_> = self.entries.iter().map(|entry| {
+            format!(
+                "{{\"index\":{},\"instruction\":\"{}\",\"accumulator_before\":{},\"accumulator_after\":{}}}",
+                entry.instruction_index,
+                entry.instruction,
+                entry.accumulator_before,
+                entry.accumulator_after,
+            )
+        }).collect();
+
+        let profile: Vec<_> = self.hit_counts.iter().enumerate().map(|(index, hits)| {
+            format!("{{\"index\":{},\"instruction\":\"{}\",\"hits\":{}}}", index, self.instructions[index], hits)
+        }).collect();
+
+        format!(
+            "{{\"result\":\"{}\",\"accumulator\":{},\"steps\":[{}],\"profile\":[{}]}}\n",
+            self.get_result_name(),
+            self.final_accumulator,
+            steps.join(","),
+            profile.join(","),
+        )
+    }
+
+    pub fn print_summary(&self) {
+        println!("Result: {}; accumulator: {}; steps: {}", self.get_result_name(), self.final_accumulator, self.entries.len());
+    }
+
+    // index of the first step at which the traces went different ways, if any
+    pub fn find_divergence(&self, other: &Trace) -> Option<usize> {
+        let common_length = self.entries.len().min(other.entries.len());
+        for step in 0..common_length {
+            let entry = &self.entries[step];
+            let other_entry = &other.entries[step];
+            if entry.instruction_index != other_entry.instruction_index || entry.instruction != other_entry.instruction || entry.accumulator_after != other_entry.accumulator_after {
+                return Some(step);
+            }
+        }
+
+        if self.entries.len() != other.entries.len() {
+            Some(common_length)
+        } else {
+            None
+        }
+    }
+
+    pub fn describe_step(&self, step: usize) -> String {
+        match self.entries.get(step) {
+            Some(entry) => format!("#{} {} (accumulator {} -> {})", entry.instruction_index, entry.instruction, entry.accumulator_before, entry.accumulator_after),
+            None => format!("{} (accumulator {})", self.get_result_name(), self.final_accumulator),
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use std::time::{Duration, Instant};
+
+use crate::board_layout::BoardLayout;
+use crate::game::Game;
+use crate::rules::Rules;
+use crate::seat_map::SeatMap;
+
+// input repeated this many times in both directions
+pub fn tile_input(lines: &[String], tiles: usize) -> Vec<String> {
+    let tiled_lines: Vec<_> = lines.iter().map(|line| line.repeat(tiles)).collect();
+    (0..tiles).flat_map(|_| tiled_lines.iter().cloned()).collect()
+}
+
+fn time_run<R: Rules + Sync>(rules: R, seat_map: &SeatMap, parallel: bool) -> (Duration, Vec<usize>, Game<R>) {
+    let mut game = Game::from_seat_map(rules, seat_map, &BoardLayout::default());
+    let mut changes_counts = vec![];
+    let start = Instant::now();
+    loop {
+        let changes_count = if parallel { game.next_step_parallel() } else { game.next_step() };
+        changes_counts.push(changes_count);
+        if changes_count == 0 || game.get_cycle().is_some() {
+            break;
+        }
+    }
+
+    (start.elapsed(), changes_counts, game)
+}
+
+pub fn run_benchmark<R: Rules + Sync>(make_rules: impl Fn() -> R, name: &str, seat_map: &SeatMap) {
+    let (sequential_time, sequential_changes, sequential_game) = time_run(make_rules(), seat_map, false);
+    let (parallel_time, parallel_changes, parallel_game) = time_run(make_rules(), seat_map, true);
+
+    let is_identical = sequential_changes == parallel_changes && sequential_game.get_states() == parallel_game.get_states();
+    println!(
+        "{}: {} iterations; sequential {:?}, parallel {:?} (x{:.2}); results {}",
+        name,
+        sequential_changes.len(),
+        sequential_time,
+        parallel_time,
+        sequential_time.as_secs_f64() / parallel_time.as_secs_f64(),
+        if is_identical { "identical" } else { "DIFFER" },
+    );
+}
@@ -0,0 +1,84 @@
+use std::error::Error;
+use std::str::FromStr;
+
+use automaton::{build_moore_offsets, build_von_neumann_offsets, Boundary, Offset, HEX_OFFSETS};
+
+use crate::rules_config::parse_state_name;
+use crate::state::State;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Neighbourhood {
+    #[default]
+    Moore,
+    VonNeumann,
+    Hex,
+}
+
+impl FromStr for Neighbourhood {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "moore" => Ok(Self::Moore),
+            "von-neumann" => Ok(Self::VonNeumann),
+            "hex" => Ok(Self::Hex),
+            _ => Err(Box::from(format!("unknown neighbourhood '{}'", s))),
+        }
+    }
+}
+
+impl Neighbourhood {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Moore => "moore",
+            Self::VonNeumann => "von-neumann",
+            Self::Hex => "hex",
+        }
+    }
+
+    // directions to the neighbours (or to the first seats in these directions for line-of-sight rules)
+    pub fn get_offsets(&self) -> Vec<Offset<2>> {
+        match self {
+            Self::Moore => build_moore_offsets(),
+            Self::VonNeumann => build_von_neumann_offsets(),
+            Self::Hex => HEX_OFFSETS.to_vec(),
+        }
+    }
+}
+
+// how the cells of the board are connected
+#[derive(Default)]
+pub struct BoardLayout {
+    pub neighbourhood: Neighbourhood,
+    pub boundary: Boundary,
+    // with Boundary::Fixed, neighbours outside of the board are in this state
+    pub border_state: State,
+}
+
+impl BoardLayout {
+    // wall, wrap, mirror or fixed:<state name>
+    pub fn set_boundary(&mut self, s: &str) -> Result<(), Box<dyn Error>> {
+        self.boundary = match s {
+            "wall" => Boundary::Wall,
+            "wrap" => Boundary::Wrap,
+            "mirror" => Boundary::Mirror,
+            _ => match s.strip_prefix("fixed:") {
+                Some(state_name) => {
+                    self.border_state = parse_state_name(state_name)?;
+                    Boundary::Fixed
+                },
+                None => return Err(Box::from(format!("unknown boundary '{}'", s))),
+            },
+        };
+        Ok(())
+    }
+
+    pub fn get_boundary_name(&self) -> String {
+        match self.boundary {
+            Boundary::Wall => "wall".to_string(),
+            Boundary::Wrap => "wrap".to_string(),
+            Boundary::Mirror => "mirror".to_string(),
+            Boundary::Fixed => format!("fixed:{}", self.border_state.get_name()),
+        }
+    }
+}
@@ -1,161 +1,184 @@
-use enum_map::EnumMap;
-use ndarray::Array2;
-use strum::IntoEnumIterator;
-
-use crate::binary::CellState;
-use crate::binary::Direction;
-use crate::binary::State;
-use crate::board_metadata::BoardMetadata;
-use crate::board_metadata::CellLocation;
+use automaton::{Boundary, Grid, NeighbourLists, SimulationReport, Topology};
+
+use crate::board_layout::BoardLayout;
 use crate::rules::Rules;
+use crate::seat_map::SeatMap;
+use crate::snapshot::Snapshot;
+use crate::state::State;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Cycle {
+    pub start_step: usize,
+    pub period: usize,
+}
 
-struct CellInfo {
-    neighbours: EnumMap<Direction, Option<CellLocation>>,
-    state: CellState,
+pub struct Game<R: Rules> {
+    game: automaton::Game<State, NeighbourLists, R>,
+    grid: Grid<2>,
+    steps_count: usize,
+    // Brent's cycle detection; the cycle start is found by simulating again from the initial board
+    initial_states: Vec<State>,
+    initial_step: usize,
+    saved_states: Vec<State>,
+    saved_population: Vec<usize>,
+    saved_step: usize,
+    save_interval: usize,
+    cycle: Option<Cycle>,
 }
 
-impl CellInfo {
-    fn new(neighbours: EnumMap<Direction, Option<CellLocation>>) -> CellInfo {
-        CellInfo {
-            neighbours,
-            state: CellState::new(),
+impl<R: Rules> Game<R> {
+    fn record_step(&mut self) {
+        self.steps_count += 1;
+
+        if self.cycle.is_some() {
+            return;
         }
-    }
 
-    fn update_neighbour_state(&mut self, direction: Direction, new_state: State) -> () {
-        self.state.update_neighbour_state(direction, new_state)
+        if self.game.get_population() == self.saved_population.as_slice() && self.game.get_states() == self.saved_states.as_slice() {
+            let period = self.steps_count - self.saved_step;
+            self.cycle = Some(Cycle {
+                start_step: self.find_cycle_start(period),
+                period,
+            });
+        } else if self.steps_count - self.saved_step == self.save_interval {
+            self.save_board();
+            self.save_interval *= 2;
+        }
     }
 
-    fn update_state(&mut self, new_state: State) {
-        self.state.update_state(new_state)
+    fn save_board(&mut self) {
+        self.saved_states.clear();
+        self.saved_states.extend_from_slice(self.game.get_states());
+        self.saved_population.clear();
+        self.saved_population.extend_from_slice(self.game.get_population());
+        self.saved_step = self.steps_count;
     }
-}
-
-pub struct Game {
-    cell_rules: Vec<State>,
-    board: Array2<CellInfo>,
-    rows: usize,
-    columns: usize,
-}
 
-impl Game {
-    // only updates the state of this cell for it and its neighbours;
-    // only state of this cell is used from new_cell
-    fn update_cell(&mut self, location: CellLocation, new_state: State) {
-        //println!("Updating cell {}:{}", location.row, location.column);
-        self.board[location].update_state(new_state);
-
-        for direction in Direction::iter() {
-            match self.board[location].neighbours[direction] {
-                Some(neighbour_location) => {
-                    //println!("Updating neighbour cell {}:{}", neighbour_location.row, neighbour_location.column);
-                    self.board[neighbour_location].update_neighbour_state(direction, new_state);
-                },
-                _ => {},
-            }
+    // first step whose board reappears after the period
+    fn find_cycle_start(&self, period: usize) -> usize {
+        let start_game = || {
+            let mut game = automaton::Game::from_states(self.game.get_rules(), self.game.get_topology(), &self.initial_states);
+            game.set_border_state(self.game.get_border_state());
+            game
+        };
+        let mut game = start_game();
+        let mut game_ahead = start_game();
+        for _ in 0..period {
+            game_ahead.next_step();
         }
-    }
-
-    fn build_cell_rules<T: Rules>() -> Vec<State> {
-        let mut result = vec![State::None; 1 << 18];
 
-        for i in 0..1usize << 18 {
-            let original_state = CellState::from_number(i as u32);
-            let mut neighbour_counts = EnumMap::new();
-            for direction in Direction::iter() {
-                neighbour_counts[original_state.get_neighbour_state(direction)] += 1usize;
-            }
-
-            let new_state = T::get_next_state(original_state.get_state(), neighbour_counts);
-            result[i] = new_state;
-            //println!("Rule #{}: for state_counts [{}, {}, {}, {}] and old state {} new state is {}", i, state_counts[0], state_counts[1], state_counts[2], state_counts[3], current_state, new_state);
+        let mut start_step = self.initial_step;
+        while game.get_states() != game_ahead.get_states() {
+            game.next_step();
+            game_ahead.next_step();
+            start_step += 1;
         }
-
-        result
+        start_step
     }
 
-    fn get_next_state(&self, cell_info: &CellInfo) -> State {
-        self.cell_rules[cell_info.state.get_number() as usize]
+    fn reset_cycle_detection(&mut self) {
+        self.initial_states = self.game.get_states().to_vec();
+        self.initial_step = self.steps_count;
+        self.save_board();
+        self.save_interval = 1;
+        self.cycle = None;
     }
 
     pub fn next_step(&mut self) -> usize {
-        let mut changes: Vec<_> = vec![];
-        for row in 0..self.rows {
-            for column in 0..self.columns {
-                let location = CellLocation::new(row, column);
-                let cell = &self.board[location];
-                let next_state = self.get_next_state(cell);
-                //println!("location: {}:{}, neighbours state {}, old state {}, next state {}", location.row, location.column, cell.neighbours_states, cell.state, next_state);
-                if next_state != cell.state.get_state() {
-                    changes.push((location, next_state));
-                }
-            }
-        }
+        let changes_count = self.game.next_step();
+        self.record_step();
+        changes_count
+    }
 
-        let changes_count = changes.len();
-        for (location, new_state) in changes {
-            self.update_cell(location, new_state);
-        }
+    // oscillating boards are not detected here, they run for max_iterations
+    pub fn run_until_stable(mut self, max_iterations: usize) -> SimulationReport<State> {
+        self.game.run_until_stable(max_iterations)
+    }
 
-        changes_count
+    // first repeated board, if any; a stable board is a cycle of period 1
+    pub fn get_cycle(&self) -> Option<Cycle> {
+        self.cycle
     }
 
-    pub fn from_input<R: Rules>(input_data: &[String]) -> Self {
-        let rows = input_data.len();
-        let columns = input_data[0].len();
-        let board_metadata = BoardMetadata::new(rows, columns);
-        let mut states = board_metadata.create_board_default();
-
-        for row in 0..rows {
-            let chars = input_data[row].chars().collect::<Vec<_>>();
-            for column in 0..columns {
-                let ch = chars[column];
-                states[[row, column]] = match ch {
-                    '.' => State::Floor,
-                    'L' => State::SeatEmpty,
-                    '#' => State::SeatOccupied,
-                    _ => State::None,
-                }
-            }
-        }
+    pub fn from_seat_map(rules: R, seat_map: &SeatMap, layout: &BoardLayout) -> Self {
+        let grid = Grid::new([seat_map.get_rows_count(), seat_map.get_columns_count()], layout.neighbourhood.get_offsets()).with_boundary(layout.boundary);
+        let states = seat_map.get_states();
 
-        let board = board_metadata.create_board_from_shape_fn(|cell_location| {
-            CellInfo::new(R::get_neighbours(cell_location, &board_metadata, &states))
-        });
+        let neighbour_locations: Vec<_> = (0..grid.get_cells_count())
+            .map(|cell| rules.get_neighbours(grid.get_location(cell), &grid, states))
+            .collect();
+        let neighbours = neighbour_locations.iter()
+            .map(|locations| locations.iter().flatten().map(|&location| grid.get_index(location)).collect())
+            .collect();
+        let border_neighbours_counts = neighbour_locations.iter()
+            .map(|locations| if layout.boundary == Boundary::Fixed { locations.iter().filter(|location| location.is_none()).count() } else { 0 })
+            .collect();
+        let topology = NeighbourLists::new(neighbours).with_border_neighbours_counts(border_neighbours_counts);
 
-        let cell_rules = Self::build_cell_rules::<R>();
+        let mut inner_game = automaton::Game::from_states(rules, topology, states);
+        inner_game.set_border_state(layout.border_state);
 
         let mut game = Game {
-            rows,
-            columns,
-            board,
-            cell_rules,
+            game: inner_game,
+            grid,
+            steps_count: 0,
+            initial_states: vec![],
+            initial_step: 0,
+            saved_states: vec![],
+            saved_population: vec![],
+            saved_step: 0,
+            save_interval: 1,
+            cycle: None,
         };
 
-        for row in 0..rows {
-            for column in 0..columns {
-                let location = CellLocation::new(row, column);
-                game.update_cell(location, states[location]);
-            }
-        }
+        game.reset_cycle_detection();
+        game
+    }
+
+    // seat positions never change, so the neighbours computed from the snapshot board are the same as originally;
+    // cycle detection starts anew
+    pub fn from_snapshot(rules: R, snapshot: &Snapshot) -> Self {
+        let mut game = Self::from_seat_map(rules, &snapshot.seat_map, &snapshot.layout);
+        game.steps_count = snapshot.iteration;
+        game.reset_cycle_detection();
+        game
+    }
+
+    pub fn get_steps_count(&self) -> usize {
+        self.steps_count
+    }
 
-        return game;
+    pub fn get_board_lines(&self) -> Vec<String> {
+        let columns = self.grid.get_shape()[1];
+        self.game.get_states().chunks(columns)
+            .map(|row| row.iter().map(|state| state.get_char()).collect())
+            .collect()
     }
 
     pub fn print_board(&self) {
-        for row in (&self.board).genrows() {
-            println!("{}", row.iter().map(|cell| {
-                match cell.state.get_state() {
-                    State::Floor => '.',
-                    State::SeatEmpty => 'L',
-                    State::SeatOccupied => '#',
-                    State::None => '0',
-                }
-            }).collect::<String>());
+        for line in self.get_board_lines() {
+            println!("{}", line);
         }
     }
 
+    pub fn get_shape(&self) -> [usize; 2] {
+        self.grid.get_shape()
+    }
+
+    // row by row
+    pub fn get_states(&self) -> &[State] {
+        self.game.get_states()
+    }
+
     pub fn get_count_of_cells_for_state(&self, state: State) -> usize {
-        (&self.board).iter().filter(|&cell| cell.state.get_state() == state).count()
+        self.game.get_count_of_cells_for_state(state)
+    }
+}
+
+impl<R: Rules + Sync> Game<R> {
+    pub fn next_step_parallel(&mut self) -> usize {
+        let changes_count = self.game.next_step_parallel();
+        self.record_step();
+        changes_count
     }
 }
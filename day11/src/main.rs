@@ -1,42 +1,257 @@
-#![feature(trait_alias)]
-
+use std::env;
+use std::error::Error;
+use std::fmt::Display;
+use std::fs;
 use std::io::{self, BufRead};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Duration;
+
+use automaton::MemoisedRules;
+
+#[macro_use] extern crate lazy_static;
 
-mod binary;
-mod board_metadata;
+mod benchmark;
+mod board_layout;
 mod game;
+mod render;
+mod report;
 mod rules;
+mod rules_config;
 mod rules_easy;
 mod rules_hard;
+mod seat_map;
+mod snapshot;
+mod state;
 
-use binary::State;
+use benchmark::{run_benchmark, tile_input};
+use board_layout::BoardLayout;
 use game::Game;
+use render::{Recorder, RenderOptions};
+use report::report_to_json;
 use rules::Rules;
+use rules_config::RulesConfig;
 use rules_easy::RulesEasy;
 use rules_hard::RulesHard;
+use seat_map::SeatMap;
+use snapshot::Snapshot;
+use state::State;
+
+const DEFAULT_ANIMATION_DELAY_MS: u64 = 100;
+const DEFAULT_BENCHMARK_TILES: usize = 20;
+const DEFAULT_REPORT_MAX_ITERATIONS: usize = 10_000;
+const DEFAULT_CHECKPOINT_INTERVAL: usize = 100;
+
+fn exit_on_error<T, E: Display>(result: Result<T, E>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        },
+    }
+}
+
+// where the simulation starts from
+#[derive(Clone, Copy)]
+enum Start<'a> {
+    Input(&'a SeatMap),
+    Snapshot(&'a Snapshot),
+}
 
-fn solve<T: Rules>(lines: &[String]) {
-    let mut game = Game::from_input::<T>(&lines);
+// file name stem for the rule description files, "easy" or "hard" for the puzzle rules
+fn get_run_name(rules_source: &str) -> String {
+    Path::new(rules_source).file_stem().map_or(rules_source.to_string(), |stem| stem.to_string_lossy().into_owned())
+}
+
+fn get_layout<'a>(start: Start<'a>, options: &'a Options) -> &'a BoardLayout {
+    match start {
+        Start::Input(_) => &options.layout,
+        Start::Snapshot(snapshot) => &snapshot.layout,
+    }
+}
+
+fn solve_with_rule_table<T: Rules + Sync>(rules: T, rules_source: &str, start: Start, options: &Options) {
+    match options.rule_table {
+        RuleTable::Direct => solve(rules, rules_source, start, options),
+        RuleTable::Memoised => {
+            let max_neighbours_count = get_layout(start, options).neighbourhood.get_offsets().len();
+            solve(MemoisedRules::new(rules, max_neighbours_count), rules_source, start, options)
+        },
+    }
+}
+
+fn solve<T: Rules + Sync>(rules: T, rules_source: &str, start: Start, options: &Options) {
+    let name = get_run_name(rules_source);
+    let layout = get_layout(start, options);
+    let mut game = match start {
+        Start::Input(seat_map) => Game::from_seat_map(rules, seat_map, layout),
+        Start::Snapshot(snapshot) => Game::from_snapshot(rules, snapshot),
+    };
+
+    if let Some(max_iterations) = options.report_max_iterations {
+        let columns = game.get_shape()[1];
+        let report = game.run_until_stable(max_iterations);
+        println!("{}", report_to_json(&name, &report, columns));
+        return;
+    }
+
+    // rule description files are stored by absolute path, so that snapshots can be resumed from any directory
+    let snapshot_rules_source = match rules_source {
+        "easy" | "hard" => rules_source.to_string(),
+        rules_path => exit_on_error(fs::canonicalize(rules_path)).to_string_lossy().into_owned(),
+    };
+
+    let mut recorder = Recorder::new(&options.render_options, &name);
 
     //game.print_board();
+    recorder.record(&game);
 
-    for i in 1.. {
-        let changes_count = game.next_step();
+    for i in game.get_steps_count() + 1.. {
+        let changes_count = if options.parallel { game.next_step_parallel() } else { game.next_step() };
+        recorder.record(&game);
         println!("Iteration {}; changed cells: {}", i, changes_count);
         //game.print_board();
+        if let Some(checkpoint_dir) = &options.checkpoint_dir {
+            if i % options.checkpoint_interval == 0 {
+                let path = checkpoint_dir.join(format!("{}.snapshot", name));
+                exit_on_error(fs::create_dir_all(checkpoint_dir));
+                exit_on_error(Snapshot::save(&path, &snapshot_rules_source, layout, i, &game.get_board_lines()));
+            }
+        }
         if changes_count == 0 {
             break;
         }
+        if let Some(cycle) = game.get_cycle() {
+            game.print_board();
+            println!("Board oscillates with period {} since iteration {}", cycle.period, cycle.start_step);
+            exit_on_error(recorder.finish());
+            return;
+        }
     }
 
     game.print_board();
     println!("Board stabilized at {} occupied seats", game.get_count_of_cells_for_state(State::SeatOccupied));
+    exit_on_error(recorder.finish());
+}
+
+fn load_rules_config(rules_path: &str) -> RulesConfig {
+    match fs::read_to_string(rules_path).map_err(|e| e.into()).and_then(|text| text.parse()) {
+        Ok(rules) => rules,
+        Err(e) => {
+            eprintln!("{}: {}", rules_path, e);
+            process::exit(1);
+        },
+    }
+}
+
+fn solve_with_rules_source(rules_source: &str, start: Start, options: &Options) {
+    match rules_source {
+        "easy" => solve_with_rule_table(RulesEasy {}, rules_source, start, options),
+        "hard" => solve_with_rule_table(RulesHard {}, rules_source, start, options),
+        rules_path => solve_with_rule_table(load_rules_config(rules_path), rules_source, start, options),
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+enum RuleTable {
+    // rules are evaluated for every cell on every step
+    #[default]
+    Direct,
+    // rules are evaluated once per combination of state and neighbour counts encountered
+    Memoised,
+}
+
+#[derive(Default)]
+struct Options {
+    rule_table: RuleTable,
+    layout: BoardLayout,
+    render_options: RenderOptions,
+    parallel: bool,
+    // compare sequential and parallel runs on the input repeated this many times in both directions
+    benchmark_tiles: Option<usize>,
+    // print a JSON report of the run, limited to this many iterations, instead of the progress
+    report_max_iterations: Option<usize>,
+    // save snapshots to <checkpoint_dir>/<name>.snapshot every checkpoint_interval iterations
+    checkpoint_dir: Option<PathBuf>,
+    checkpoint_interval: usize,
+    // continue the simulation from this snapshot instead of the input
+    resume_path: Option<PathBuf>,
+}
+
+// options are --animate[=<delay in ms>], --gif=<dir>, --png=<dir>, --parallel, --benchmark[=<tiles>],
+// --rule-table=direct|memoised, --boundary=wall|wrap|mirror|fixed:<floor|empty|occupied>
+// --neighbourhood=moore|von-neumann|hex, --report[=<max iterations>], --checkpoint=<dir>,
+// --checkpoint-interval=<iterations> and --resume=<snapshot file>;
+// all other arguments are rule description files
+fn parse_args(args: &[String]) -> Result<(Options, Vec<String>), Box<dyn Error>> {
+    let mut options = Options {
+        checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
+        ..Options::default()
+    };
+    let mut rules_paths = vec![];
+    for arg in args {
+        match arg.split_once('=') {
+            None if arg == "--animate" => options.render_options.animation_delay = Some(Duration::from_millis(DEFAULT_ANIMATION_DELAY_MS)),
+            Some(("--animate", delay)) => options.render_options.animation_delay = Some(Duration::from_millis(delay.parse()?)),
+            Some(("--gif", dir)) => options.render_options.gif_dir = Some(PathBuf::from(dir)),
+            Some(("--png", dir)) => options.render_options.png_dir = Some(PathBuf::from(dir)),
+            None if arg == "--parallel" => options.parallel = true,
+            None if arg == "--benchmark" => options.benchmark_tiles = Some(DEFAULT_BENCHMARK_TILES),
+            Some(("--benchmark", tiles)) => options.benchmark_tiles = Some(tiles.parse()?),
+            Some(("--rule-table", "direct")) => options.rule_table = RuleTable::Direct,
+            Some(("--rule-table", "memoised")) => options.rule_table = RuleTable::Memoised,
+            Some(("--boundary", boundary)) => options.layout.set_boundary(boundary)?,
+            Some(("--neighbourhood", neighbourhood)) => options.layout.neighbourhood = neighbourhood.parse()?,
+            None if arg == "--report" => options.report_max_iterations = Some(DEFAULT_REPORT_MAX_ITERATIONS),
+            Some(("--report", max_iterations)) => options.report_max_iterations = Some(max_iterations.parse()?),
+            Some(("--checkpoint", dir)) => options.checkpoint_dir = Some(PathBuf::from(dir)),
+            Some(("--checkpoint-interval", interval)) => {
+                options.checkpoint_interval = interval.parse()?;
+                if options.checkpoint_interval == 0 {
+                    return Err(Box::from("checkpoint interval must be positive"));
+                }
+            },
+            Some(("--resume", path)) => options.resume_path = Some(PathBuf::from(path)),
+            _ if arg.starts_with("--") => return Err(Box::from(format!("unknown option {}", arg))),
+            _ => rules_paths.push(arg.clone()),
+        }
+    }
+    if options.resume_path.is_some() && !rules_paths.is_empty() {
+        return Err(Box::from("rules are taken from the snapshot when resuming"));
+    }
+    Ok((options, rules_paths))
 }
 
 fn main() {
+    let args: Vec<_> = env::args().skip(1).collect();
+    let (options, rules_paths) = exit_on_error(parse_args(&args));
+
+    if let Some(resume_path) = &options.resume_path {
+        let snapshot = exit_on_error(Snapshot::load(resume_path));
+        solve_with_rules_source(&snapshot.rules_source, Start::Snapshot(&snapshot), &options);
+        return;
+    }
+
     let stdin = io::stdin();
     let lines: Vec<_> = stdin.lock().lines().map(|line| line.unwrap()).collect();
+    let seat_map = exit_on_error(SeatMap::parse(&lines));
 
-    solve::<RulesEasy>(&lines);
-    solve::<RulesHard>(&lines);
+    if let Some(tiles) = options.benchmark_tiles {
+        let seat_map = exit_on_error(SeatMap::parse(&tile_input(&lines[..seat_map.get_rows_count()], tiles)));
+        run_benchmark(|| RulesEasy {}, "easy", &seat_map);
+        run_benchmark(|| RulesHard {}, "hard", &seat_map);
+        return;
+    }
+
+    // without rule description files, both puzzle rules are used
+    if rules_paths.is_empty() {
+        solve_with_rules_source("easy", Start::Input(&seat_map), &options);
+        solve_with_rules_source("hard", Start::Input(&seat_map), &options);
+        return;
+    }
+
+    for rules_path in rules_paths {
+        solve_with_rules_source(&rules_path, Start::Input(&seat_map), &options);
+    }
 }
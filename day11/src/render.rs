@@ -0,0 +1,157 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use automaton::CellState;
+
+use crate::game::Game;
+use crate::rules::Rules;
+use crate::state::State;
+
+// every cell is a square of this many pixels in exported images
+const CELL_SIZE: usize = 4;
+
+const GIF_FRAME_DELAY_CENTISECONDS: u16 = 10;
+
+// indexed by State::get_index()
+const PALETTE: [[u8; 3]; State::COUNT] = [
+    [0, 0, 0],
+    [64, 64, 64],
+    [46, 160, 67],
+    [218, 54, 51],
+    [210, 153, 34],
+    [110, 64, 170],
+];
+
+fn get_ansi_colour(state: State) -> &'static str {
+    match state {
+        State::None => "\x1b[30m",
+        State::Floor => "\x1b[90m",
+        State::SeatEmpty => "\x1b[32m",
+        State::SeatOccupied => "\x1b[31m",
+        State::SeatReserved => "\x1b[33m",
+        State::SeatBroken => "\x1b[35m",
+    }
+}
+
+#[derive(Default)]
+pub struct RenderOptions {
+    // redraw the board in the terminal after every iteration, pausing for this long
+    pub animation_delay: Option<Duration>,
+    // <gif_dir>/<name>.gif
+    pub gif_dir: Option<PathBuf>,
+    // <png_dir>/<name>/frame-NNNN.png
+    pub png_dir: Option<PathBuf>,
+}
+
+impl RenderOptions {
+    fn needs_frames(&self) -> bool {
+        self.gif_dir.is_some() || self.png_dir.is_some()
+    }
+}
+
+pub struct Recorder<'a> {
+    options: &'a RenderOptions,
+    name: String,
+    shape: [usize; 2],
+    // state indices of every recorded board, row by row
+    frames: Vec<Vec<u8>>,
+}
+
+impl<'a> Recorder<'a> {
+    pub fn new(options: &'a RenderOptions, name: &str) -> Self {
+        Recorder {
+            options,
+            name: name.to_string(),
+            shape: [0, 0],
+            frames: vec![],
+        }
+    }
+
+    pub fn record<R: Rules>(&mut self, game: &Game<R>) {
+        if let Some(delay) = self.options.animation_delay {
+            print_board_coloured(game);
+            thread::sleep(delay);
+        }
+
+        if self.options.needs_frames() {
+            self.shape = game.get_shape();
+            self.frames.push(game.get_states().iter().map(|state| state.get_index() as u8).collect());
+        }
+    }
+
+    fn get_image_size(&self) -> (usize, usize) {
+        (self.shape[1] * CELL_SIZE, self.shape[0] * CELL_SIZE)
+    }
+
+    fn scale_frame(&self, frame: &[u8]) -> Vec<u8> {
+        let (width, height) = self.get_image_size();
+        let columns = self.shape[1];
+        let mut result = Vec::with_capacity(width * height);
+        for row in frame.chunks(columns) {
+            let scaled_row: Vec<_> = row.iter().flat_map(|&index| std::iter::repeat_n(index, CELL_SIZE)).collect();
+            for _ in 0..CELL_SIZE {
+                result.extend_from_slice(&scaled_row);
+            }
+        }
+        result
+    }
+
+    fn write_png_frames(&self, png_dir: &Path) -> Result<(), Box<dyn Error>> {
+        let (width, height) = self.get_image_size();
+        let too_large = |_| format!("image of {}x{} pixels is too large for PNG", width, height);
+        let (width, height) = (u32::try_from(width).map_err(too_large)?, u32::try_from(height).map_err(too_large)?);
+        let frames_dir = png_dir.join(&self.name);
+        fs::create_dir_all(&frames_dir)?;
+        for (index, frame) in self.frames.iter().enumerate() {
+            let file = File::create(frames_dir.join(format!("frame-{:04}.png", index)))?;
+            let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+            encoder.set_color(png::ColorType::Indexed);
+            encoder.set_depth(png::BitDepth::Eight);
+            encoder.set_palette(PALETTE.concat());
+            encoder.write_header()?.write_image_data(&self.scale_frame(frame))?;
+        }
+        Ok(())
+    }
+
+    fn write_gif(&self, gif_dir: &Path) -> Result<(), Box<dyn Error>> {
+        let (width, height) = self.get_image_size();
+        // GIF dimensions are 16-bit
+        let too_large = |_| format!("image of {}x{} pixels is too large for GIF", width, height);
+        let (width, height) = (u16::try_from(width).map_err(too_large)?, u16::try_from(height).map_err(too_large)?);
+        fs::create_dir_all(gif_dir)?;
+        let file = File::create(gif_dir.join(format!("{}.gif", self.name)))?;
+        let mut encoder = gif::Encoder::new(BufWriter::new(file), width, height, &PALETTE.concat())?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+        for frame in &self.frames {
+            let mut gif_frame = gif::Frame::from_indexed_pixels(width, height, self.scale_frame(frame), None);
+            gif_frame.delay = GIF_FRAME_DELAY_CENTISECONDS;
+            encoder.write_frame(&gif_frame)?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<(), Box<dyn Error>> {
+        if let Some(png_dir) = &self.options.png_dir {
+            self.write_png_frames(png_dir)?;
+        }
+        if let Some(gif_dir) = &self.options.gif_dir {
+            self.write_gif(gif_dir)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn print_board_coloured<R: Rules>(game: &Game<R>) {
+    // move cursor home and clear the screen
+    print!("\x1b[H\x1b[2J");
+    let columns = game.get_shape()[1];
+    for row in game.get_states().chunks(columns) {
+        let line: String = row.iter().map(|&state| format!("{}{}", get_ansi_colour(state), state.get_char())).collect();
+        println!("{}\x1b[0m", line);
+    }
+}
@@ -0,0 +1,51 @@
+use automaton::{CellState, Outcome, SimulationReport};
+
+use crate::state::State;
+
+// JSON string literal, with quotes
+fn get_string_json(s: &str) -> String {
+    let mut result = String::from("\"");
+    for ch in s.chars() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => result.push(ch),
+        }
+    }
+    result.push('"');
+    result
+}
+
+fn get_population_json(population: &[usize]) -> String {
+    let fields: Vec<_> = population.iter()
+        .enumerate()
+        .map(|(index, count)| format!("\"{}\":{}", State::from_index(index).get_name(), count))
+        .collect();
+    format!("{{{}}}", fields.join(","))
+}
+
+pub fn report_to_json(name: &str, report: &SimulationReport<State>, columns: usize) -> String {
+    let outcome = match report.outcome {
+        Outcome::Stabilised => "stabilised",
+        Outcome::ReachedIterationCap => "reached_iteration_cap",
+    };
+    let changes_counts: Vec<_> = report.changes_counts.iter().map(|count| count.to_string()).collect();
+    let population_history: Vec<_> = report.population_history.iter().map(|population| get_population_json(population)).collect();
+    let final_board: Vec<_> = report.final_states.chunks(columns)
+        .map(|row| format!("\"{}\"", row.iter().map(|state| state.get_char()).collect::<String>()))
+        .collect();
+
+    format!(
+        "{{\"name\":{},\"outcome\":\"{}\",\"iterations\":{},\"changes_counts\":[{}],\"population_history\":[{}],\"final_board\":[{}]}}",
+        get_string_json(name),
+        outcome,
+        report.get_iterations_count(),
+        changes_counts.join(","),
+        population_history.join(","),
+        final_board.join(","),
+    )
+}
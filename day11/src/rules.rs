@@ -1,9 +1,113 @@
-use enum_map::EnumMap;
-use ndarray::Array2;
-use crate::binary::{Direction, State};
-use crate::board_metadata::{BoardMetadata,CellLocation};
-
-pub trait Rules {
-    fn get_next_state(current_state: State, neighbour_counts: EnumMap<State, usize>) -> State;
-    fn get_neighbours(cell_location: CellLocation, board_metadata: &BoardMetadata, original_states: &Array2<State>) -> EnumMap<Direction, Option<CellLocation>>;
+use automaton::{Grid, Location, MemoisedRules, Offset};
+use crate::state::State;
+
+pub trait Rules: automaton::Rules<State> {
+    // None for directions in which there is nothing but the border
+    fn get_neighbours(&self, cell_location: Location<2>, grid: &Grid<2>, original_states: &[State]) -> Vec<Option<Location<2>>>;
+}
+
+impl<R: Rules> Rules for MemoisedRules<State, R> {
+    fn get_neighbours(&self, cell_location: Location<2>, grid: &Grid<2>, original_states: &[State]) -> Vec<Option<Location<2>>> {
+        self.get_rules().get_neighbours(cell_location, grid, original_states)
+    }
+}
+
+pub fn get_adjacent_neighbours(cell_location: Location<2>, grid: &Grid<2>) -> Vec<Option<Location<2>>> {
+    grid.get_offsets().iter()
+        .map(|&offset| grid.get_neighbour_location(cell_location, offset))
+        .collect()
+}
+
+// the ray goes straight on an unfolded board, so it is reflected by mirrored edges and continues through wrapped ones
+fn find_visible_neighbour(cell_location: Location<2>, direction: Offset<2>, grid: &Grid<2>, original_states: &[State]) -> Option<Location<2>> {
+    for distance in 1.. {
+        let offset = [direction[0] * distance, direction[1] * distance];
+        match grid.get_neighbour_location(cell_location, offset) {
+            // on wrapped or mirrored boards, the ray always comes back to where it started eventually
+            Some(location) if location == cell_location => {
+                return None;
+            },
+            Some(location) => {
+                if original_states[grid.get_index(location)].is_seat() {
+                    return Some(location);
+                }
+            },
+            None => {
+                return None;
+            },
+        }
+    }
+    unreachable!()
+}
+
+// first seat in every direction
+pub fn get_visible_neighbours(cell_location: Location<2>, grid: &Grid<2>, original_states: &[State]) -> Vec<Option<Location<2>>> {
+    grid.get_offsets().iter()
+        .map(|&direction| find_visible_neighbour(cell_location, direction, grid, original_states))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use automaton::Boundary;
+
+    use super::*;
+
+    // '#' for seats, '.' for floor
+    fn parse_board(rows: &[&str]) -> Vec<State> {
+        rows.iter()
+            .flat_map(|row| row.chars().map(|ch| if ch == '#' { State::SeatEmpty } else { State::Floor }))
+            .collect()
+    }
+
+    #[test]
+    fn mirrored_adjacent_neighbours_are_reflected_about_the_edge() {
+        let grid = Grid::new_moore([3, 4]).with_boundary(Boundary::Mirror);
+        let neighbours = get_adjacent_neighbours([0, 0], &grid);
+        assert_eq!(neighbours, vec![
+            Some([1, 1]), Some([1, 0]), Some([1, 1]),
+            Some([0, 1]), Some([0, 1]),
+            Some([1, 1]), Some([1, 0]), Some([1, 1]),
+        ]);
+    }
+
+    #[test]
+    fn mirrored_line_of_sight_bounces_off_the_edge() {
+        let grid = Grid::new_moore([3, 5]).with_boundary(Boundary::Mirror);
+        let states = parse_board(&[
+            ".....",
+            "#....",
+            "...#.",
+        ]);
+        // up and right: [0, 1], then reflected to [1, 2] and [2, 3]
+        assert_eq!(find_visible_neighbour([1, 0], [-1, 1], &grid, &states), Some([2, 3]));
+        // left: reflected straight back along the row, through the cell itself
+        assert_eq!(find_visible_neighbour([1, 0], [0, -1], &grid, &states), None);
+    }
+
+    #[test]
+    fn mirrored_line_of_sight_without_seats_ends() {
+        let grid = Grid::new_moore([4, 7]).with_boundary(Boundary::Mirror);
+        let states = parse_board(&[
+            ".......",
+            ".......",
+            "..#....",
+            ".......",
+        ]);
+        for &direction in grid.get_offsets() {
+            assert_eq!(find_visible_neighbour([2, 2], direction, &grid, &states), None);
+        }
+    }
+
+    #[test]
+    fn wrapped_line_of_sight_continues_on_the_opposite_side() {
+        let grid = Grid::new_moore([3, 5]).with_boundary(Boundary::Wrap);
+        let states = parse_board(&[
+            ".....",
+            "#...#",
+            ".....",
+        ]);
+        assert_eq!(find_visible_neighbour([1, 0], [0, -1], &grid, &states), Some([1, 4]));
+        assert_eq!(find_visible_neighbour([1, 0], [1, 0], &grid, &states), None);
+    }
 }
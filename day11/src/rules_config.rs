@@ -0,0 +1,147 @@
+use std::error::Error;
+use std::str::FromStr;
+
+use automaton::{Grid, Location, NeighbourCounts};
+use regex::Regex;
+
+use crate::state::State;
+use crate::rules::{get_adjacent_neighbours, get_visible_neighbours, Rules};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NeighbourStrategy {
+    Adjacent,
+    LineOfSight,
+}
+
+impl FromStr for NeighbourStrategy {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "adjacent" => Ok(Self::Adjacent),
+            "line-of-sight" => Ok(Self::LineOfSight),
+            _ => Err(Box::from(format!("unknown neighbour strategy '{}'", s))),
+        }
+    }
+}
+
+pub fn parse_state_name(s: &str) -> Result<State, Box<dyn Error>> {
+    match s {
+        "floor" => Ok(State::Floor),
+        "empty" => Ok(State::SeatEmpty),
+        "occupied" => Ok(State::SeatOccupied),
+        "reserved" => Ok(State::SeatReserved),
+        "broken" => Ok(State::SeatBroken),
+        _ => Err(Box::from(format!("unknown state '{}'", s))),
+    }
+}
+
+// comma-separated list of counts and inclusive ranges, e.g. "0-3, 5"
+fn parse_counts(s: &str) -> Result<Vec<u16>, Box<dyn Error>> {
+    let mut result = vec![];
+    for part in s.split(',').map(|part| part.trim()).filter(|part| !part.is_empty()) {
+        match part.split_once('-') {
+            Some((from, to)) => {
+                let from: u16 = from.trim().parse()?;
+                let to: u16 = to.trim().parse()?;
+                if from > to {
+                    return Err(Box::from(format!("empty range '{}'", part)));
+                }
+                result.extend(from..=to);
+            },
+            None => result.push(part.parse()?),
+        }
+    }
+    Ok(result)
+}
+
+// "key = value" lines: neighbours (adjacent or line-of-sight), birth and survival counts of occupied neighbours,
+// optional static (floor) and crowded (occupied) state lists;
+// states are floor, empty, occupied, reserved and broken, and reserved and broken seats never change
+pub struct RulesConfig {
+    neighbour_strategy: NeighbourStrategy,
+    birth: Vec<u16>,
+    survival: Vec<u16>,
+    static_states: Vec<State>,
+    crowded_states: Vec<State>,
+}
+
+fn parse_state_names(s: &str) -> Result<Vec<State>, Box<dyn Error>> {
+    s.split(',')
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+        .map(parse_state_name)
+        .collect()
+}
+
+impl RulesConfig {
+    fn is_static(&self, state: State) -> bool {
+        matches!(state, State::None | State::SeatReserved | State::SeatBroken) || self.static_states.contains(&state)
+    }
+}
+
+impl FromStr for RulesConfig {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        lazy_static! {
+            static ref LINE_RE: Regex = Regex::new(r"^([a-z]+)\s*=\s*(.*)$").unwrap();
+        }
+
+        let mut neighbour_strategy = None;
+        let mut birth = None;
+        let mut survival = None;
+        let mut static_states = vec![State::Floor];
+        let mut crowded_states = vec![State::SeatOccupied];
+
+        for (line_number, line) in s.lines().enumerate().map(|(index, line)| (index + 1, line.trim())) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let with_line = |error: Box<dyn Error>| Box::<dyn Error>::from(format!("line {}: {}", line_number, error));
+            let captures = LINE_RE.captures(line).ok_or_else(|| with_line(Box::from("expected 'key = value'")))?;
+            let value = captures[2].trim();
+            match &captures[1] {
+                "neighbours" => neighbour_strategy = Some(value.parse().map_err(with_line)?),
+                "birth" => birth = Some(parse_counts(value).map_err(with_line)?),
+                "survival" => survival = Some(parse_counts(value).map_err(with_line)?),
+                "static" => static_states = parse_state_names(value).map_err(with_line)?,
+                "crowded" => crowded_states = parse_state_names(value).map_err(with_line)?,
+                key => return Err(with_line(Box::from(format!("unknown key '{}'", key)))),
+            }
+        }
+
+        Ok(RulesConfig {
+            neighbour_strategy: neighbour_strategy.ok_or("missing 'neighbours'")?,
+            birth: birth.ok_or("missing 'birth'")?,
+            survival: survival.ok_or("missing 'survival'")?,
+            static_states,
+            crowded_states,
+        })
+    }
+}
+
+impl automaton::Rules<State> for RulesConfig {
+    fn get_next_state(&self, current_state: State, neighbour_counts: NeighbourCounts<State>) -> State {
+        if self.is_static(current_state) {
+            return current_state;
+        }
+
+        let occupied_count: u16 = self.crowded_states.iter().map(|&state| neighbour_counts[state]).sum();
+        match current_state {
+            State::SeatEmpty => if self.birth.contains(&occupied_count) { State::SeatOccupied } else { State::SeatEmpty },
+            State::SeatOccupied => if self.survival.contains(&occupied_count) { State::SeatOccupied } else { State::SeatEmpty },
+            other => other
+        }
+    }
+}
+
+impl Rules for RulesConfig {
+    fn get_neighbours(&self, cell_location: Location<2>, grid: &Grid<2>, original_states: &[State]) -> Vec<Option<Location<2>>> {
+        match self.neighbour_strategy {
+            NeighbourStrategy::Adjacent => get_adjacent_neighbours(cell_location, grid),
+            NeighbourStrategy::LineOfSight => get_visible_neighbours(cell_location, grid, original_states),
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::state::State;
+
+// rows and columns are 1-based
+#[derive(Debug)]
+pub enum SeatMapError {
+    Empty,
+    UnknownCharacter { row: usize, column: usize, ch: char },
+    RaggedRow { row: usize, length: usize, expected_length: usize },
+}
+
+impl fmt::Display for SeatMapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "seat map is empty"),
+            Self::UnknownCharacter { row, column, ch } => write!(f, "{}:{}: unknown character '{}'", row, column, ch),
+            Self::RaggedRow { row, length, expected_length } => write!(f, "{}: row has {} cells, expected {}", row, length, expected_length),
+        }
+    }
+}
+
+impl Error for SeatMapError {}
+
+pub struct SeatMap {
+    rows: usize,
+    columns: usize,
+    // row by row
+    states: Vec<State>,
+}
+
+impl SeatMap {
+    // seat map ends at the end of input or at the first blank line
+    pub fn parse(lines: &[String]) -> Result<Self, SeatMapError> {
+        let lines: Vec<_> = lines.iter()
+            .map(|line| line.trim_end_matches('\r'))
+            .take_while(|line| !line.is_empty())
+            .collect();
+
+        let columns = lines.first().ok_or(SeatMapError::Empty)?.chars().count();
+        let mut states = Vec::with_capacity(lines.len() * columns);
+        for (row, line) in lines.iter().enumerate() {
+            let length = line.chars().count();
+            if length != columns {
+                return Err(SeatMapError::RaggedRow { row: row + 1, length, expected_length: columns });
+            }
+
+            for (column, ch) in line.chars().enumerate() {
+                states.push(State::from_char(ch).ok_or(SeatMapError::UnknownCharacter { row: row + 1, column: column + 1, ch })?);
+            }
+        }
+
+        Ok(SeatMap {
+            rows: lines.len(),
+            columns,
+            states,
+        })
+    }
+
+    pub fn get_rows_count(&self) -> usize {
+        self.rows
+    }
+
+    pub fn get_columns_count(&self) -> usize {
+        self.columns
+    }
+
+    pub fn get_states(&self) -> &[State] {
+        &self.states
+    }
+}
@@ -0,0 +1,60 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use crate::board_layout::BoardLayout;
+use crate::seat_map::SeatMap;
+
+// "key = value" lines (rules, neighbourhood, boundary, iteration), a blank line, then the board in the input format;
+// rules are "easy", "hard" or a path to a rule description file
+pub struct Snapshot {
+    pub rules_source: String,
+    pub layout: BoardLayout,
+    pub iteration: usize,
+    pub seat_map: SeatMap,
+}
+
+impl Snapshot {
+    pub fn save(path: &Path, rules_source: &str, layout: &BoardLayout, iteration: usize, board_lines: &[String]) -> Result<(), Box<dyn Error>> {
+        let header = format!(
+            "rules = {}\nneighbourhood = {}\nboundary = {}\niteration = {}\n\n",
+            rules_source,
+            layout.neighbourhood.get_name(),
+            layout.get_boundary_name(),
+            iteration,
+        );
+
+        // write to a temporary file first, so that an interrupted save does not destroy the previous snapshot
+        let temporary_path = path.with_extension("tmp");
+        fs::write(&temporary_path, header + &board_lines.join("\n") + "\n")?;
+        fs::rename(&temporary_path, path)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = fs::read_to_string(path)?;
+        let lines: Vec<_> = text.lines().map(|line| line.to_string()).collect();
+        let header_length = lines.iter().position(|line| line.trim().is_empty()).ok_or("missing board")?;
+
+        let mut rules_source = None;
+        let mut layout = BoardLayout::default();
+        let mut iteration = None;
+        for line in &lines[..header_length] {
+            let (key, value) = line.split_once('=').ok_or_else(|| format!("expected 'key = value', got '{}'", line))?;
+            match key.trim() {
+                "rules" => rules_source = Some(value.trim().to_string()),
+                "neighbourhood" => layout.neighbourhood = value.trim().parse()?,
+                "boundary" => layout.set_boundary(value.trim())?,
+                "iteration" => iteration = Some(value.trim().parse()?),
+                key => return Err(Box::from(format!("unknown key '{}'", key))),
+            }
+        }
+
+        Ok(Snapshot {
+            rules_source: rules_source.ok_or("missing 'rules'")?,
+            layout,
+            iteration: iteration.ok_or("missing 'iteration'")?,
+            seat_map: SeatMap::parse(&lines[header_length + 1..])?,
+        })
+    }
+}
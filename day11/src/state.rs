@@ -0,0 +1,79 @@
+use automaton::CellState;
+
+#[derive(Clone, Copy, Default, Eq, Hash, PartialEq)]
+pub enum State {
+    #[default]
+    None,
+    Floor,
+    SeatEmpty,
+    SeatOccupied,
+    // seats which never change and are never taken
+    SeatReserved,
+    SeatBroken,
+}
+
+impl State {
+    pub fn from_char(ch: char) -> Option<Self> {
+        match ch {
+            '.' => Some(Self::Floor),
+            'L' => Some(Self::SeatEmpty),
+            '#' => Some(Self::SeatOccupied),
+            'R' => Some(Self::SeatReserved),
+            'X' => Some(Self::SeatBroken),
+            _ => None,
+        }
+    }
+
+    pub fn get_char(&self) -> char {
+        match self {
+            Self::Floor => '.',
+            Self::SeatEmpty => 'L',
+            Self::SeatOccupied => '#',
+            Self::SeatReserved => 'R',
+            Self::SeatBroken => 'X',
+            Self::None => '0',
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Floor => "floor",
+            Self::SeatEmpty => "empty",
+            Self::SeatOccupied => "occupied",
+            Self::SeatReserved => "reserved",
+            Self::SeatBroken => "broken",
+        }
+    }
+
+    pub fn is_seat(&self) -> bool {
+        matches!(self, Self::SeatEmpty | Self::SeatOccupied | Self::SeatReserved | Self::SeatBroken)
+    }
+}
+
+impl CellState for State {
+    const COUNT: usize = 6;
+
+    fn from_index(index: usize) -> Self {
+        match index {
+            0 => Self::None,
+            1 => Self::Floor,
+            2 => Self::SeatEmpty,
+            3 => Self::SeatOccupied,
+            4 => Self::SeatReserved,
+            5 => Self::SeatBroken,
+            _ => panic!("unsupported number {}", index),
+        }
+    }
+
+    fn get_index(&self) -> usize {
+        match self {
+            Self::None => 0,
+            Self::Floor => 1,
+            Self::SeatEmpty => 2,
+            Self::SeatOccupied => 3,
+            Self::SeatReserved => 4,
+            Self::SeatBroken => 5,
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use automaton::{LifeState, Rules};
+
+use crate::game::Game;
+
+// alive cells as an ASCII PLY point cloud: x = column, y = -row, z = third coordinate; only the slice at zero further coordinates
+pub fn write_ply<const D: usize, R: Rules<LifeState>>(path: &Path, game: &Game<D, R>) -> std::io::Result<()> {
+    let vertices: Vec<_> = game.get_alive_points()
+        .filter(|point| point.iter().skip(3).all(|&coordinate| coordinate == 0))
+        .map(|point| [point[1], -point[0], if D > 2 { point[2] } else { 0 }])
+        .collect();
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut writer = BufWriter::new(File::create(path)?);
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format ascii 1.0")?;
+    writeln!(writer, "element vertex {}", vertices.len())?;
+    writeln!(writer, "property int x")?;
+    writeln!(writer, "property int y")?;
+    writeln!(writer, "property int z")?;
+    writeln!(writer, "end_header")?;
+    for [x, y, z] in vertices {
+        writeln!(writer, "{} {} {}", x, y, z)?;
+    }
+    writer.flush()
+}
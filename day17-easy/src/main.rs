@@ -1,39 +1,127 @@
-#![feature(trait_alias)]
-
+use std::env;
+use std::error::Error;
+use std::fmt::Display;
 use std::io::{self, BufRead};
+use std::path::PathBuf;
+use std::process;
+
+use automaton::{LifeRules, LifeState, Rules};
 
-mod binary;
-mod board_metadata;
+mod export;
 mod game;
-mod rules;
-mod rules_easy;
 
-use binary::State;
+use export::write_ply;
 use game::Game;
-use rules::Rules;
-use rules_easy::RulesEasy;
 
-fn solve<T: Rules>(lines: &[String]) {
-    let mut game = Game::from_input::<T>(&lines);
+const DEFAULT_DIMENSIONS: usize = 3;
+const DEFAULT_RULE: &str = "B3/S23";
+// as in the puzzle
+const DEFAULT_CYCLES_COUNT: usize = 6;
+
+fn exit_on_error<T, E: Display>(result: Result<T, E>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        },
+    }
+}
+
+// <ply_dir>/iteration-NNNN.ply
+fn export_board<const D: usize, T: Rules<LifeState>>(game: &Game<D, T>, iteration: usize, options: &Options) {
+    if let Some(ply_dir) = &options.ply_dir {
+        exit_on_error(write_ply(&ply_dir.join(format!("iteration-{:04}.ply", iteration)), game));
+    }
+}
+
+fn solve<const D: usize, T: Rules<LifeState>>(rules: T, lines: &[String], options: &Options) {
+    let mut game = Game::<D, T>::from_input(rules, lines, options.symmetric);
 
-    //game.print_board();
+    if options.print_boards {
+        game.print_board();
+    }
+    export_board(&game, 0, options);
 
-    for i in 1.. {
+    let cycles_count = options.cycles_count;
+    for i in 1..=cycles_count {
         let changes_count = game.next_step();
-        println!("Iteration {}; changed cells: {}; alive_cells: {}", i, changes_count, game.get_count_of_cells_for_state(State::Alive));
-        //game.print_board();
+        println!("Iteration {}; changed cells: {}; alive_cells: {}", i, changes_count, game.get_count_of_cells_for_state(LifeState::Alive));
+        if options.print_boards {
+            game.print_board();
+        }
+        export_board(&game, i, options);
         if changes_count == 0 {
-            break;
+            println!("Board stabilized at {} alive cells", game.get_count_of_cells_for_state(LifeState::Alive));
+            return;
         }
     }
 
-    //game.print_board();
-    println!("Board stabilized at {} occupied seats", game.get_count_of_cells_for_state(State::Alive));
+    println!("After {} cycles, {} cells are alive", cycles_count, game.get_count_of_cells_for_state(LifeState::Alive));
+}
+
+struct Options {
+    dimensions: usize,
+    // only simulate non-negative coordinates beyond the first two, as the board is symmetric there
+    symmetric: bool,
+    // print every slice of the board after every iteration
+    print_boards: bool,
+    // export the board after every iteration as a point cloud
+    ply_dir: Option<PathBuf>,
+    // rule string in B/S notation
+    rule: String,
+    // stop after this many cycles, unless the board stabilises earlier
+    cycles_count: usize,
+}
+
+// optional arguments: --dimensions=<2..5>, --symmetric, --print, --ply=<dir>, --rule=<B.../S...>
+// and --cycles=<count>
+fn parse_args(args: &[String]) -> Result<Options, Box<dyn Error>> {
+    let mut options = Options {
+        dimensions: DEFAULT_DIMENSIONS,
+        symmetric: false,
+        print_boards: false,
+        ply_dir: None,
+        rule: DEFAULT_RULE.to_string(),
+        cycles_count: DEFAULT_CYCLES_COUNT,
+    };
+    for arg in args {
+        match arg.split_once('=') {
+            Some(("--dimensions", dimensions)) => options.dimensions = dimensions.parse()?,
+            Some(("--ply", dir)) => options.ply_dir = Some(PathBuf::from(dir)),
+            Some(("--rule", rule)) => options.rule = rule.to_string(),
+            Some(("--cycles", cycles_count)) => options.cycles_count = cycles_count.parse()?,
+            None if arg == "--symmetric" => options.symmetric = true,
+            None if arg == "--print" => options.print_boards = true,
+            _ => return Err(Box::from(format!("unknown argument '{}'", arg))),
+        }
+    }
+    Ok(options)
+}
+
+fn parse_rules(rule: &str) -> Result<LifeRules, Box<dyn Error>> {
+    let rules: LifeRules = rule.parse()?;
+    // otherwise, the infinitely many dead cells around the board would become alive
+    if rules.is_birth(0) {
+        return Err(Box::from("birth at 0 neighbours is not supported on an unbounded board"));
+    }
+    Ok(rules)
 }
 
 fn main() {
+    let args: Vec<_> = env::args().skip(1).collect();
+    let options = exit_on_error(parse_args(&args));
+
+    let rules = exit_on_error(parse_rules(&options.rule));
+
     let stdin = io::stdin();
     let lines: Vec<_> = stdin.lock().lines().map(|line| line.unwrap()).collect();
 
-    solve::<RulesEasy>(&lines);
+    match options.dimensions {
+        2 => solve::<2, _>(rules, &lines, &options),
+        3 => solve::<3, _>(rules, &lines, &options),
+        4 => solve::<4, _>(rules, &lines, &options),
+        5 => solve::<5, _>(rules, &lines, &options),
+        dimensions => exit_on_error(Err(format!("unsupported number of dimensions {}", dimensions))),
+    }
 }
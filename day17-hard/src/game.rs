@@ -1,124 +1,58 @@
-use ndarray::Array4;
+use std::error::Error;
 
-use crate::locations::{CellLocation, MINICUBE_DIFF_ARRAY};
+use automaton::{Grid, LifeRules, LifeState, Topology};
 
-struct CellInfo {
-    is_alive: bool,
-    alive_minicube_count: usize,
+// Conway's rules, B3/S23
+fn get_rules() -> LifeRules {
+    LifeRules::new(&[3], &[2, 3])
 }
 
-impl Default for CellInfo {
-    fn default() -> CellInfo {
-        CellInfo {
-            is_alive: false,
-            alive_minicube_count: 0,
-        }
-    }
-}
-
-impl CellInfo {
-    pub fn set_alive(&mut self) {
-        self.is_alive = true;
-    }
-
-    pub fn add_alive_minicube(&mut self) {
-        self.alive_minicube_count += 1;
-    }
+// the dense board takes several bytes per cell, so larger boards would not fit in memory
+const MAX_CELLS_COUNT: usize = 1 << 27;
 
-    pub fn set_dead(&mut self) {
-        self.is_alive = false;
-    }
-
-    pub fn add_dead_minicube(&mut self) {
-        self.alive_minicube_count -= 1;
-    }
+// D-dimensional Conway hypercubes; the input is a two-dimensional slice in the middle of the extra dimensions
+pub struct Game<const D: usize> {
+    game: automaton::Game<LifeState, Grid<D>, LifeRules>,
 }
 
-pub const MAX_NUMBER_OF_STEPS: usize = 30;
-
-pub struct Game {
-    board: Array4<CellInfo>,
-    corner: CellLocation,
-}
-
-impl Game {
-    fn make_alive(&mut self, cell_location: CellLocation) {
-        self.board[cell_location].set_alive();
-        for &diff in &MINICUBE_DIFF_ARRAY {
-            self.board[cell_location + diff].add_alive_minicube();
-        }
-    }
-
-    fn make_dead(&mut self, cell_location: CellLocation) {
-        self.board[cell_location].set_dead();
-        for &diff in &MINICUBE_DIFF_ARRAY {
-            self.board[cell_location + diff].add_dead_minicube();
-        }
-    }
-
+impl<const D: usize> Game<D> {
     pub fn next_step(&mut self) -> usize {
-        let mut new_alive: Vec<_> = vec![];
-        let mut new_dead: Vec<_> = vec![];
-        for x in 0..self.corner.x {
-            for y in 0..self.corner.y {
-                for z in 0..self.corner.z {
-                    for w in 0..self.corner.w {
-                        let location = CellLocation { x, y, z, w };
-                        match &self.board[location] {
-                            CellInfo { is_alive: true, alive_minicube_count } if *alive_minicube_count < 3 || *alive_minicube_count > 4 => {
-                                new_dead.push(location);
-                            },
-                            CellInfo { is_alive: false, alive_minicube_count: 3 } => {
-                                new_alive.push(location);
-                            },
-                            _ => {}
-                        }
-                    }
-                }
-            }
-        }
-
-        let changes_count = new_alive.len() + new_dead.len();
-        for location in new_alive {
-            self.make_alive(location);
-        }
-        for location in new_dead {
-            self.make_dead(location);
-        }
-
-        changes_count
+        self.game.next_step()
     }
 
-    pub fn from_input(input_data: &[String]) -> Self {
+    // activity spreads by at most one cell per step, so the board has enough room for max_steps_count steps
+    pub fn from_input(input_data: &[String], max_steps_count: usize) -> Result<Self, Box<dyn Error>> {
         let rows = input_data.len();
         let columns = input_data[0].len();
-        let corner = CellLocation {
-            x: rows + 2*MAX_NUMBER_OF_STEPS,
-            y: columns + 2*MAX_NUMBER_OF_STEPS,
-            z: 1 + 2*MAX_NUMBER_OF_STEPS,
-            w: 1 + 2*MAX_NUMBER_OF_STEPS,
-        };
-        let board = Array4::default((corner.x, corner.y, corner.z, corner.w));
-        let mut game = Game {
-            board,
-            corner,
-        };
+        let padding = max_steps_count.saturating_mul(2);
+        let mut shape = [padding.saturating_add(1); D];
+        shape[0] = padding.saturating_add(rows);
+        shape[1] = padding.saturating_add(columns);
+        let cells_count = shape.iter().try_fold(1usize, |result, &size| result.checked_mul(size));
+        if !matches!(cells_count, Some(cells_count) if cells_count <= MAX_CELLS_COUNT) {
+            return Err(Box::from(format!("board of shape {:?} for {} steps is too large, try fewer cycles", shape, max_steps_count)));
+        }
+
+        let grid = Grid::new_moore(shape);
+        let mut states = vec![LifeState::Dead; grid.get_cells_count()];
 
-        for row in 0..rows {
-            let chars = input_data[row].chars().collect::<Vec<_>>();
-            for column in 0..columns {
-                let ch = chars[column];
+        for (row, line) in input_data.iter().enumerate() {
+            for (column, ch) in line.chars().take(columns).enumerate() {
                 if ch == '#' {
-                    game.make_alive(CellLocation { x: row + MAX_NUMBER_OF_STEPS, y: column + MAX_NUMBER_OF_STEPS, z: MAX_NUMBER_OF_STEPS, w: MAX_NUMBER_OF_STEPS });
+                    let mut location = [max_steps_count; D];
+                    location[0] = row + max_steps_count;
+                    location[1] = column + max_steps_count;
+                    states[grid.get_index(location)] = LifeState::Alive;
                 }
             }
         }
 
-        return game;
+        Ok(Game {
+            game: automaton::Game::from_states(get_rules(), grid, &states),
+        })
     }
 
     pub fn get_alive_count(&self) -> usize {
-        (&self.board).iter().filter(|&cell| cell.is_alive).count()
+        self.game.get_count_of_cells_for_state(LifeState::Alive)
     }
 }
-
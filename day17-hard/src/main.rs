@@ -1,17 +1,31 @@
+use std::env;
+use std::error::Error;
+use std::fmt::Display;
 use std::io::{self, BufRead};
+use std::process;
 
 mod game;
-mod locations;
 
-use crate::game::{Game, MAX_NUMBER_OF_STEPS};
+use crate::game::Game;
 
-fn main() {
-    let stdin = io::stdin();
-    let lines: Vec<_> = stdin.lock().lines().map(|line| line.unwrap()).collect();
+const DEFAULT_DIMENSIONS: usize = 4;
+// as in the puzzle
+const DEFAULT_MAX_STEPS_COUNT: usize = 6;
+
+fn exit_on_error<T, E: Display>(result: Result<T, E>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        },
+    }
+}
 
-    let mut game = Game::from_input(&lines);
+fn solve<const D: usize>(lines: &[String], max_steps_count: usize) {
+    let mut game = exit_on_error(Game::<D>::from_input(lines, max_steps_count));
 
-    for i in 1..MAX_NUMBER_OF_STEPS {
+    for i in 1..=max_steps_count {
         let changes_count = game.next_step();
         println!("Iteration {}; changed cells: {}; alive_cells: {}", i, changes_count, game.get_alive_count());
         if changes_count == 0 {
@@ -19,3 +33,41 @@ fn main() {
         }
     }
 }
+
+struct Options {
+    dimensions: usize,
+    // the board is allocated with room for this many steps, so keep it small for higher dimensions
+    max_steps_count: usize,
+}
+
+// optional arguments: --dimensions=<3..6> and --cycles=<max steps count>
+fn parse_args(args: &[String]) -> Result<Options, Box<dyn Error>> {
+    let mut options = Options {
+        dimensions: DEFAULT_DIMENSIONS,
+        max_steps_count: DEFAULT_MAX_STEPS_COUNT,
+    };
+    for arg in args {
+        match arg.split_once('=') {
+            Some(("--dimensions", dimensions)) => options.dimensions = dimensions.parse()?,
+            Some(("--cycles", max_steps_count)) => options.max_steps_count = max_steps_count.parse()?,
+            _ => return Err(Box::from(format!("unknown argument '{}'", arg))),
+        }
+    }
+    Ok(options)
+}
+
+fn main() {
+    let args: Vec<_> = env::args().skip(1).collect();
+    let options = exit_on_error(parse_args(&args));
+
+    let stdin = io::stdin();
+    let lines: Vec<_> = stdin.lock().lines().map(|line| line.unwrap()).collect();
+
+    match options.dimensions {
+        3 => solve::<3>(&lines, options.max_steps_count),
+        4 => solve::<4>(&lines, options.max_steps_count),
+        5 => solve::<5>(&lines, options.max_steps_count),
+        6 => solve::<6>(&lines, options.max_steps_count),
+        dimensions => exit_on_error(Err(format!("unsupported number of dimensions {}", dimensions))),
+    }
+}
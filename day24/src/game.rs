@@ -1,86 +1,17 @@
-use ndarray::Array2;
+use automaton::{Grid, LifeRules, LifeState, Topology, HEX_OFFSETS};
 
-use crate::locations::{CellLocation, MINICUBE_DIFF_ARRAY};
-
-struct CellInfo {
-    is_alive: bool, // alive = black, dead = white
-    alive_minicube_count: usize,
-}
-
-impl Default for CellInfo {
-    fn default() -> CellInfo {
-        CellInfo {
-            is_alive: false,
-            alive_minicube_count: 0,
-        }
-    }
-}
-
-impl CellInfo {
-    pub fn set_alive(&mut self) {
-        self.is_alive = true;
-    }
-
-    pub fn add_alive_minicube(&mut self) {
-        self.alive_minicube_count += 1;
-    }
-
-    pub fn set_dead(&mut self) {
-        self.is_alive = false;
-    }
-
-    pub fn add_dead_minicube(&mut self) {
-        self.alive_minicube_count -= 1;
-    }
+// alive = black, dead = white; B2/S12 on the hexagonal grid
+fn get_rules() -> LifeRules {
+    LifeRules::new(&[2], &[1, 2])
 }
 
 pub struct Game {
-    board: Array2<CellInfo>,
-    corner: CellLocation,
+    game: automaton::Game<LifeState, Grid<2>, LifeRules>,
 }
 
 impl Game {
-    fn make_alive(&mut self, cell_location: CellLocation) {
-        self.board[cell_location].set_alive();
-        for &diff in &MINICUBE_DIFF_ARRAY {
-            self.board[cell_location + diff].add_alive_minicube();
-        }
-    }
-
-    fn make_dead(&mut self, cell_location: CellLocation) {
-        self.board[cell_location].set_dead();
-        for &diff in &MINICUBE_DIFF_ARRAY {
-            self.board[cell_location + diff].add_dead_minicube();
-        }
-    }
-
     pub fn next_step(&mut self) -> usize {
-        let mut new_alive: Vec<_> = vec![];
-        let mut new_dead: Vec<_> = vec![];
-        for x in 0..self.corner.x {
-            for y in 0..self.corner.y {
-                let location = CellLocation { x, y };
-                match &self.board[location] {
-                    CellInfo { is_alive: true, alive_minicube_count } if *alive_minicube_count == 0 || *alive_minicube_count > 2 => {
-                        new_dead.push(location);
-                    },
-                    CellInfo { is_alive: false, alive_minicube_count: 2 } => {
-                        new_alive.push(location);
-                    },
-                    _ => {}
-                }
-            }
-        }
-
-        let changes_count = new_alive.len() + new_dead.len();
-        for location in new_alive {
-            self.make_alive(location);
-        }
-        for location in new_dead {
-            self.make_dead(location);
-        }
-
-        changes_count
+        self.game.next_step()
     }
 
     pub fn from_input(input_data: &[(i16, i16)], max_number_of_steps: usize) -> Self {
@@ -94,28 +25,19 @@ impl Game {
         let size_y = (input_size_y as usize) + max_number_of_steps * 2;
         let offset_x = (max_number_of_steps as i16) - min_x;
         let offset_y = (max_number_of_steps as i16) - min_y;
-        let corner = CellLocation {
-            x: size_x,
-            y: size_y,
-        };
-        let board = Array2::default((corner.x, corner.y));
-        let mut game = Game {
-            board,
-            corner,
-        };
+        let grid = Grid::new([size_x, size_y], HEX_OFFSETS.to_vec());
+        let mut states = vec![LifeState::Dead; grid.get_cells_count()];
 
         for (input_x, input_y) in input_data {
-            game.make_alive(CellLocation {
-                x: (input_x + offset_x) as usize,
-                y: (input_y + offset_y) as usize,
-            });
+            states[grid.get_index([(input_x + offset_x) as usize, (input_y + offset_y) as usize])] = LifeState::Alive;
         }
 
-        return game;
+        Game {
+            game: automaton::Game::from_states(get_rules(), grid, &states),
+        }
     }
 
     pub fn get_alive_count(&self) -> usize {
-        (&self.board).iter().filter(|&cell| cell.is_alive).count()
+        self.game.get_count_of_cells_for_state(LifeState::Alive)
     }
 }
-
@@ -2,7 +2,6 @@ use std::collections::HashSet;
 use std::io::{self, BufRead};
 
 mod game;
-mod locations;
 
 use crate::game::Game;
 